@@ -190,11 +190,11 @@ impl TryFrom<&ValidatorNodeConfig> for ValidatorConfiguration {
         );
         Ok(ValidatorConfiguration {
             owner_account_address: private_identity.account_address,
-            owner_account_public_key: private_identity.account_private_key.public_key(),
+            owner_account_public_key: private_identity.account_private_key.public_key().into(),
             operator_account_address: private_identity.account_address,
-            operator_account_public_key: private_identity.account_private_key.public_key(),
+            operator_account_public_key: private_identity.account_private_key.public_key().into(),
             voter_account_address: private_identity.account_address,
-            voter_account_public_key: private_identity.account_private_key.public_key(),
+            voter_account_public_key: private_identity.account_private_key.public_key().into(),
             consensus_public_key: Some(private_identity.consensus_private_key.public_key()),
             proof_of_possession: Some(bls12381::ProofOfPossession::create(
                 &private_identity.consensus_private_key,
@@ -211,6 +211,7 @@ impl TryFrom<&ValidatorNodeConfig> for ValidatorConfiguration {
             commission_percentage: config.commission_percentage,
             // Default to joining the genesis validator set.
             join_during_genesis: true,
+            publish_validator_address: true,
         })
     }
 }
@@ -2,20 +2,27 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_config::config::HANDSHAKE_VERSION;
-use aptos_crypto::{bls12381, ed25519::Ed25519PublicKey, x25519};
+use aptos_crypto::{
+    bls12381,
+    ed25519::{Ed25519PublicKey, ED25519_PUBLIC_KEY_LENGTH},
+    multi_ed25519::MultiEd25519PublicKey,
+    x25519, CryptoMaterialError, ValidCryptoMaterial, ValidCryptoMaterialStringExt,
+};
+use aptos_logger::warn;
 use aptos_types::{
     account_address::AccountAddress,
-    chain_id::ChainId,
+    chain_id::{deserialize_config_chain_id, ChainId},
     network_address::{DnsName, NetworkAddress, Protocol},
     transaction::authenticator::AuthenticationKey,
 };
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::{
     convert::TryFrom,
+    fmt,
     fs::File,
     io::Read,
-    net::{Ipv4Addr, Ipv6Addr, ToSocketAddrs},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, ToSocketAddrs},
     path::Path,
     str::FromStr,
 };
@@ -23,14 +30,15 @@ use vm_genesis::{AccountBalance, EmployeePool, Validator, ValidatorWithCommissio
 
 /// Template for setting up Github for Genesis
 ///
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Layout {
     /// Root key for the blockchain
     /// TODO: In the future, we won't need a root key
     pub root_key: Option<Ed25519PublicKey>,
     /// List of usernames or identifiers
     pub users: Vec<String>,
-    /// ChainId for the target network
+    /// ChainId for the target network, either a number or a named network e.g. `testnet`
+    #[serde(deserialize_with = "deserialize_config_chain_id")]
     pub chain_id: ChainId,
     /// Whether to allow new validators to join the set after genesis
     ///
@@ -62,6 +70,27 @@ pub struct Layout {
     pub voting_power_increase_limit: u64,
     /// Total supply of coins
     pub total_supply: Option<u64>,
+    /// Minimum percentage of the total supply that validators joining during genesis must stake
+    ///
+    /// Only used for mainnet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_staked_supply_percent: Option<u64>,
+    /// Maximum percentage of the total supply that validators joining during genesis can stake
+    ///
+    /// Only used for mainnet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_staked_supply_percent: Option<u64>,
+    /// Minimum number of operators that must together hold more than a third of the voting power
+    /// of the validators joining during genesis
+    ///
+    /// Only used for mainnet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_nakamoto_coefficient_third: Option<u64>,
+    /// Maximum commission percentage (0-100%) that any validator can charge
+    ///
+    /// Only used for mainnet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_commission_percentage: Option<u64>,
 }
 
 impl Layout {
@@ -77,8 +106,172 @@ impl Layout {
 
         Ok(serde_yaml::from_str(&contents)?)
     }
+
+    /// Runs all checks on the layout's values, returning every failure
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let errors: Vec<String> = LAYOUT_CHECKS
+            .iter()
+            .filter_map(|check| check(self).err())
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn check_epoch_duration(&self) -> Result<(), String> {
+        if self.epoch_duration_secs == 0 {
+            return Err("epoch_duration_secs must be greater than 0".to_string());
+        }
+        check_duration(
+            "epoch_duration_secs",
+            self.epoch_duration_secs,
+            MIN_EPOCH_DURATION_SECS,
+            MAX_EPOCH_DURATION_SECS,
+        )
+    }
+
+    pub fn check_stake_bounds(&self) -> Result<(), String> {
+        if self.max_stake == 0 {
+            return Err("max_stake must be greater than 0".to_string());
+        }
+        if self.min_stake > self.max_stake {
+            return Err(format!(
+                "min_stake {} must not be greater than max_stake {}",
+                self.min_stake, self.max_stake
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn check_recurring_lockup_duration(&self) -> Result<(), String> {
+        if self.recurring_lockup_duration_secs < self.epoch_duration_secs {
+            return Err(format!(
+                "recurring_lockup_duration_secs {} must be at least epoch_duration_secs {}",
+                self.recurring_lockup_duration_secs, self.epoch_duration_secs
+            ));
+        }
+        check_duration(
+            "recurring_lockup_duration_secs",
+            self.recurring_lockup_duration_secs,
+            0,
+            MAX_RECURRING_LOCKUP_DURATION_SECS,
+        )
+    }
+
+    pub fn check_rewards_apy_percentage(&self) -> Result<(), String> {
+        if self.rewards_apy_percentage > 100 {
+            return Err(format!(
+                "rewards_apy_percentage {} must not be greater than 100",
+                self.rewards_apy_percentage
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn check_voting_duration(&self) -> Result<(), String> {
+        if self.voting_duration_secs == 0 {
+            return Err("voting_duration_secs must be greater than 0".to_string());
+        }
+        // Stake must stay locked up for the whole vote
+        if self.voting_duration_secs > self.recurring_lockup_duration_secs {
+            return Err(format!(
+                "voting_duration_secs {} must not be greater than recurring_lockup_duration_secs {}",
+                self.voting_duration_secs, self.recurring_lockup_duration_secs
+            ));
+        }
+        check_duration(
+            "voting_duration_secs",
+            self.voting_duration_secs,
+            MIN_VOTING_DURATION_SECS,
+            u64::MAX,
+        )
+    }
+
+    pub fn check_voting_power_increase_limit(&self) -> Result<(), String> {
+        if self.voting_power_increase_limit == 0 || self.voting_power_increase_limit > 50 {
+            return Err(format!(
+                "voting_power_increase_limit {} must be between 1 and 50",
+                self.voting_power_increase_limit
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn check_staked_supply_percent(&self) -> Result<(), String> {
+        for (name, percent) in [
+            ("min_staked_supply_percent", self.min_staked_supply_percent),
+            ("max_staked_supply_percent", self.max_staked_supply_percent),
+        ] {
+            if let Some(percent) = percent {
+                if percent > 100 {
+                    return Err(format!("{} {} must not be greater than 100", name, percent));
+                }
+            }
+        }
+        if let (Some(min), Some(max)) = (
+            self.min_staked_supply_percent,
+            self.max_staked_supply_percent,
+        ) {
+            if min > max {
+                return Err(format!(
+                    "min_staked_supply_percent {} must not be greater than max_staked_supply_percent {}",
+                    min, max
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn check_max_commission_percentage(&self) -> Result<(), String> {
+        if let Some(max) = self.max_commission_percentage {
+            if max > 100 {
+                return Err(format!(
+                    "max_commission_percentage {} must not be greater than 100",
+                    max
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Shortest epoch the layout allows.  Every epoch change reconfigures the validator set, so much
+/// shorter epochs leave little time for anything else
+pub const MIN_EPOCH_DURATION_SECS: u64 = 10;
+/// Longest epoch the layout allows, rewards and validator set changes only apply between epochs
+pub const MAX_EPOCH_DURATION_SECS: u64 = 7 * 24 * 60 * 60;
+/// Longest recurring lockup the layout allows, as stake can't be withdrawn until it ends
+pub const MAX_RECURRING_LOCKUP_DURATION_SECS: u64 = 365 * 24 * 60 * 60;
+/// Shortest governance vote the layout allows
+pub const MIN_VOTING_DURATION_SECS: u64 = 60;
+
+/// Checks that a duration is within `min..=max`, naming the field if it isn't
+fn check_duration(name: &str, secs: u64, min: u64, max: u64) -> Result<(), String> {
+    if secs < min {
+        Err(format!("{} {} must be at least {}", name, secs, min))
+    } else if secs > max {
+        Err(format!("{} {} must be at most {}", name, secs, max))
+    } else {
+        Ok(())
+    }
 }
 
+/// A check on a layout's values, returning a description of the problem if it fails
+pub type LayoutCheck = fn(&Layout) -> Result<(), String>;
+
+const LAYOUT_CHECKS: [LayoutCheck; 8] = [
+    Layout::check_epoch_duration,
+    Layout::check_stake_bounds,
+    Layout::check_recurring_lockup_duration,
+    Layout::check_rewards_apy_percentage,
+    Layout::check_voting_duration,
+    Layout::check_voting_power_increase_limit,
+    Layout::check_staked_supply_percent,
+    Layout::check_max_commission_percentage,
+];
+
 impl Default for Layout {
     fn default() -> Self {
         Layout {
@@ -97,6 +290,105 @@ impl Default for Layout {
             voting_duration_secs: 43_200,
             voting_power_increase_limit: 20,
             total_supply: None,
+            min_staked_supply_percent: None,
+            max_staked_supply_percent: None,
+            min_nakamoto_coefficient_third: None,
+            max_commission_percentage: None,
+        }
+    }
+}
+
+/// Public key of an account, either a single ed25519 key or a multi-ed25519 key
+///
+/// Both are encoded as the hex of their bytes, and told apart by length.  A multi-ed25519 key is
+/// its public keys followed by a one byte threshold, so it's never the length of a single key.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AccountPublicKey {
+    Ed25519(Ed25519PublicKey),
+    MultiEd25519(MultiEd25519PublicKey),
+}
+
+impl AccountPublicKey {
+    /// Authentication key for the key's scheme, which the account address is derived from
+    pub fn authentication_key(&self) -> AuthenticationKey {
+        match self {
+            AccountPublicKey::Ed25519(key) => AuthenticationKey::ed25519(key),
+            AccountPublicKey::MultiEd25519(key) => AuthenticationKey::multi_ed25519(key),
+        }
+    }
+}
+
+impl From<Ed25519PublicKey> for AccountPublicKey {
+    fn from(key: Ed25519PublicKey) -> Self {
+        AccountPublicKey::Ed25519(key)
+    }
+}
+
+impl From<MultiEd25519PublicKey> for AccountPublicKey {
+    fn from(key: MultiEd25519PublicKey) -> Self {
+        AccountPublicKey::MultiEd25519(key)
+    }
+}
+
+impl TryFrom<&[u8]> for AccountPublicKey {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() == ED25519_PUBLIC_KEY_LENGTH {
+            Ok(AccountPublicKey::Ed25519(Ed25519PublicKey::try_from(
+                bytes,
+            )?))
+        } else {
+            Ok(AccountPublicKey::MultiEd25519(
+                MultiEd25519PublicKey::try_from(bytes)?,
+            ))
+        }
+    }
+}
+
+impl ValidCryptoMaterial for AccountPublicKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            AccountPublicKey::Ed25519(key) => key.to_bytes().to_vec(),
+            AccountPublicKey::MultiEd25519(key) => key.to_bytes(),
+        }
+    }
+}
+
+impl fmt::Display for AccountPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_encoded_string().map_err(|_| fmt::Error)?)
+    }
+}
+
+impl Serialize for AccountPublicKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            self.to_encoded_string()
+                .map_err(<S::Error as serde::ser::Error>::custom)?
+                .serialize(serializer)
+        } else {
+            serializer.serialize_bytes(&self.to_bytes())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for AccountPublicKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let encoded = String::deserialize(deserializer)?;
+            AccountPublicKey::from_encoded_string(&encoded)
+                .map_err(<D::Error as serde::de::Error>::custom)
+        } else {
+            let bytes = <Vec<u8>>::deserialize(deserializer)?;
+            AccountPublicKey::try_from(bytes.as_slice())
+                .map_err(<D::Error as serde::de::Error>::custom)
         }
     }
 }
@@ -108,11 +400,11 @@ pub struct ValidatorConfiguration {
     /// Account address
     pub owner_account_address: AccountAddress,
     /// Key used for signing transactions with the account
-    pub owner_account_public_key: Ed25519PublicKey,
+    pub owner_account_public_key: AccountPublicKey,
     pub operator_account_address: AccountAddress,
-    pub operator_account_public_key: Ed25519PublicKey,
+    pub operator_account_public_key: AccountPublicKey,
     pub voter_account_address: AccountAddress,
-    pub voter_account_public_key: Ed25519PublicKey,
+    pub voter_account_public_key: AccountPublicKey,
     /// Key used for signing in consensus
     #[serde(skip_serializing_if = "Option::is_none")]
     pub consensus_public_key: Option<bls12381::PublicKey>,
@@ -139,6 +431,17 @@ pub struct ValidatorConfiguration {
     /// If set to false, the validator will be fully initialized but won't be added to the
     /// validator set.
     pub join_during_genesis: bool,
+    /// Whether the validator host should be published in the seed peers and summary generated
+    /// alongside genesis.  The validator host is still added on-chain regardless.
+    ///
+    /// This allows operators to keep their validator behind a private network, and only
+    /// advertise their full node.
+    #[serde(default = "default_publish_validator_address")]
+    pub publish_validator_address: bool,
+}
+
+fn default_publish_validator_address() -> bool {
+    true
 }
 
 impl TryFrom<ValidatorConfiguration> for ValidatorWithCommissionRate {
@@ -187,7 +490,7 @@ impl TryFrom<ValidatorConfiguration> for Validator {
             vec![]
         };
 
-        let auth_key = AuthenticationKey::ed25519(&config.owner_account_public_key);
+        let auth_key = config.owner_account_public_key.authentication_key();
         let derived_address = auth_key.derived_address();
         if config.owner_account_address != derived_address {
             return Err(anyhow::Error::msg(format!(
@@ -196,7 +499,7 @@ impl TryFrom<ValidatorConfiguration> for Validator {
             )));
         }
 
-        let auth_key = AuthenticationKey::ed25519(&config.operator_account_public_key);
+        let auth_key = config.operator_account_public_key.authentication_key();
         let derived_address = auth_key.derived_address();
         if config.operator_account_address != derived_address {
             return Err(anyhow::Error::msg(format!(
@@ -205,7 +508,7 @@ impl TryFrom<ValidatorConfiguration> for Validator {
             )));
         }
 
-        let auth_key = AuthenticationKey::ed25519(&config.voter_account_public_key);
+        let auth_key = config.voter_account_public_key.authentication_key();
         let derived_address = auth_key.derived_address();
         if config.voter_account_address != derived_address {
             return Err(anyhow::Error::msg(format!(
@@ -277,6 +580,41 @@ impl HostAndPort {
             handshake_protocol,
         ])?)
     }
+
+    /// Resolves a DNS name to an IP address, keeping the port
+    ///
+    /// Hosts that are already IP addresses are returned as is.  Only A and AAAA records are
+    /// looked up, through the system resolver.  The resolver's order isn't stable, so a name
+    /// with several addresses resolves to the lowest IPv4 address, or the lowest IPv6 address if
+    /// it has none, with a warning.
+    pub fn resolve(&self) -> anyhow::Result<HostAndPort> {
+        let host = self.host.to_string();
+        if IpAddr::from_str(&host).is_ok() {
+            return Ok(self.clone());
+        }
+
+        let addresses: BTreeSet<IpAddr> = (host.as_str(), self.port)
+            .to_socket_addrs()?
+            .map(|address| address.ip())
+            .collect();
+        let address = addresses
+            .iter()
+            .find(|address| address.is_ipv4())
+            .or_else(|| addresses.iter().next())
+            .ok_or_else(|| anyhow::Error::msg(format!("{} has no addresses", host)))?;
+        if addresses.len() > 1 {
+            warn!(
+                "{} has {} addresses, using {}",
+                host,
+                addresses.len(),
+                address
+            );
+        }
+        Ok(HostAndPort {
+            host: DnsName::try_from(address.to_string())?,
+            port: self.port,
+        })
+    }
 }
 
 impl TryFrom<&NetworkAddress> for HostAndPort {
@@ -305,14 +643,20 @@ impl FromStr for HostAndPort {
     }
 }
 
+impl fmt::Display for HostAndPort {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.host, self.port)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OwnerConfiguration {
     pub owner_account_address: AccountAddress,
-    pub owner_account_public_key: Ed25519PublicKey,
+    pub owner_account_public_key: AccountPublicKey,
     pub voter_account_address: AccountAddress,
-    pub voter_account_public_key: Ed25519PublicKey,
+    pub voter_account_public_key: AccountPublicKey,
     pub operator_account_address: AccountAddress,
-    pub operator_account_public_key: Ed25519PublicKey,
+    pub operator_account_public_key: AccountPublicKey,
     pub stake_amount: u64,
     pub commission_percentage: u64,
     pub join_during_genesis: bool,
@@ -321,13 +665,15 @@ pub struct OwnerConfiguration {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OperatorConfiguration {
     pub operator_account_address: AccountAddress,
-    pub operator_account_public_key: Ed25519PublicKey,
+    pub operator_account_public_key: AccountPublicKey,
     pub consensus_public_key: bls12381::PublicKey,
     pub consensus_proof_of_possession: bls12381::ProofOfPossession,
     pub validator_network_public_key: x25519::PublicKey,
     pub validator_host: HostAndPort,
     pub full_node_network_public_key: Option<x25519::PublicKey>,
     pub full_node_host: Option<HostAndPort>,
+    #[serde(default = "default_publish_validator_address")]
+    pub publish_validator_address: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -350,9 +696,143 @@ pub struct StringOperatorConfiguration {
     pub consensus_public_key: Option<String>,
     pub consensus_proof_of_possession: Option<String>,
     pub validator_network_public_key: Option<String>,
-    pub validator_host: HostAndPort,
+    #[serde(default, deserialize_with = "deserialize_host_string")]
+    pub validator_host: Option<String>,
     pub full_node_network_public_key: Option<String>,
     pub full_node_host: Option<HostAndPort>,
+    pub publish_validator_address: Option<String>,
+}
+
+/// Reads a host as either `host:port`, or the `host` and `port` fields that `HostAndPort` is
+/// written as
+fn deserialize_host_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum HostString {
+        HostAndPort(HostAndPort),
+        String(String),
+    }
+
+    Ok(
+        Option::<HostString>::deserialize(deserializer)?.map(|host| match host {
+            HostString::HostAndPort(host) => host.to_string(),
+            HostString::String(host) => host,
+        }),
+    )
+}
+
+/// Octas in one APT
+pub const OCTAS_PER_APT: u64 = 100_000_000;
+/// Decimal places of an APT amount, one for each digit of octas
+const APT_DECIMALS: usize = 8;
+const APT_SUFFIX: &str = "APT";
+const NUMBER_EXAMPLES: &str = "e.g. 100000000, 100_000_000, or 100,000,000";
+const AMOUNT_EXAMPLES: &str = "e.g. 100000000, 100_000_000, 100,000,000, or 1_000.5 APT";
+
+/// Parses a whole number, which can have `_` or `,` between groups of three digits
+pub fn parse_number(value: &str) -> anyhow::Result<u64> {
+    parse_digits(value.trim()).map_err(|reason| {
+        anyhow::anyhow!(
+            "Invalid number '{}', {}.  Numbers can be {}",
+            value,
+            reason,
+            NUMBER_EXAMPLES
+        )
+    })
+}
+
+/// Parses an amount in octas, or in APT if it ends with `APT`
+///
+/// Octas are a whole number, which can have `_` or `,` between groups of three digits.  APT can
+/// also have up to 8 decimal places, so that it's always a whole number of octas.
+pub fn parse_amount(value: &str) -> anyhow::Result<u64> {
+    let trimmed = value.trim();
+    let amount = if let Some(apt) = trimmed.strip_suffix(APT_SUFFIX) {
+        parse_apt(apt.trim_end())
+    } else {
+        parse_digits(trimmed)
+    };
+    amount.map_err(|reason| {
+        anyhow::anyhow!(
+            "Invalid amount '{}', {}.  Amounts can be {}",
+            value,
+            reason,
+            AMOUNT_EXAMPLES
+        )
+    })
+}
+
+fn parse_digits(value: &str) -> Result<u64, String> {
+    if value.is_empty() {
+        return Err("it's empty".to_string());
+    }
+    if value.contains('.') {
+        return Err(format!("only amounts in {} can have decimals", APT_SUFFIX));
+    }
+    let separator = match (value.contains('_'), value.contains(',')) {
+        (true, true) => return Err("it mixes `_` and `,` separators".to_string()),
+        (true, false) => Some('_'),
+        (false, true) => Some(','),
+        (false, false) => None,
+    };
+
+    if let Some(separator) = separator {
+        let groups: Vec<_> = value.split(separator).collect();
+        let first_group = groups[0].len();
+        if first_group == 0 || first_group > 3 || groups[1..].iter().any(|group| group.len() != 3) {
+            return Err(format!(
+                "`{}` must separate groups of three digits",
+                separator
+            ));
+        }
+    }
+
+    let digits: String = value.chars().filter(|c| Some(*c) != separator).collect();
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err("it has characters other than digits and separators".to_string());
+    }
+    u64::from_str(&digits).map_err(|_| "it's too large".to_string())
+}
+
+fn parse_apt(value: &str) -> Result<u64, String> {
+    let (whole, decimals) = match value.split_once('.') {
+        Some((_, "")) => return Err("the decimal point must be followed by digits".to_string()),
+        Some((whole, decimals)) => (whole, decimals),
+        None => (value, ""),
+    };
+    let whole = parse_digits(whole)?;
+    if decimals.len() > APT_DECIMALS {
+        return Err(format!(
+            "{} can't have more than {} decimal places",
+            APT_SUFFIX, APT_DECIMALS
+        ));
+    }
+    if !decimals.chars().all(|c| c.is_ascii_digit()) {
+        return Err("the decimal places must only be digits".to_string());
+    }
+
+    // Pad the decimal places out to octas e.g. `.5` is 50000000 octas
+    let fraction = if decimals.is_empty() {
+        0
+    } else {
+        u64::from_str(&format!("{:0<width$}", decimals, width = APT_DECIMALS))
+            .map_err(|err| err.to_string())?
+    };
+    whole
+        .checked_mul(OCTAS_PER_APT)
+        .and_then(|octas| octas.checked_add(fraction))
+        .ok_or_else(|| "it's too large".to_string())
+}
+
+/// A balance in the balances file, either a number of octas or an amount to parse
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BalanceValue {
+    Octas(u64),
+    Amount(String),
 }
 
 #[derive(Debug, Clone)]
@@ -374,7 +854,21 @@ impl<'de> Deserialize<'de> for AccountBalanceMap {
     where
         D: Deserializer<'de>,
     {
-        let account_balances = <Vec<BTreeMap<AccountAddress, u64>>>::deserialize(deserializer)?;
+        let values = <Vec<BTreeMap<AccountAddress, BalanceValue>>>::deserialize(deserializer)?;
+        let mut account_balances = Vec::new();
+        for entry in values {
+            let mut balances = BTreeMap::new();
+            for (account_address, value) in entry {
+                let balance = match value {
+                    BalanceValue::Octas(octas) => octas,
+                    BalanceValue::Amount(amount) => {
+                        parse_amount(&amount).map_err(<D::Error as serde::de::Error>::custom)?
+                    }
+                };
+                balances.insert(account_address, balance);
+            }
+            account_balances.push(balances);
+        }
         Ok(AccountBalanceMap { account_balances })
     }
 }
@@ -478,23 +972,8 @@ impl TryFrom<EmployeePoolMap> for Vec<EmployeePool> {
             }
 
             // Check vesting schedule adds up properly
-            let mut numerators = 0;
-            let denominator = pool.vesting_schedule_denominator;
-            let mut last_numerator = 0;
-            for numerator in pool.vesting_schedule_numerators.iter() {
-                numerators += *numerator;
-                last_numerator = *numerator;
-            }
-
-            if numerators > denominator {
-                anyhow::bail!(
-                    "Numerators {} add up over the denominator {} for pool #{}",
-                    numerators,
-                    denominator,
-                    i
-                )
-            } else if (denominator - numerators) % last_numerator != 0 {
-                anyhow::bail!("Numerators don't add up to the denominator {} (with the last one {} being repeated for pool #{}", denominator, last_numerator, i)
+            if let Err(err) = pool.check_vesting_schedule() {
+                anyhow::bail!("{} for pool #{}", err, i)
             }
 
             // I'm going to assume no one wants to pay more than 50% of their rewards away
@@ -548,6 +1027,60 @@ pub struct EmployeePoolConfig {
     pub beneficiary_resetter: AccountAddress,
 }
 
+impl EmployeePoolConfig {
+    /// Checks that the vesting schedule fully vests, and that genesis can create it
+    ///
+    /// Each numerator is the fraction vested in one period, and the last one is repeated until
+    /// everything has vested, so leading zeros act as a cliff.
+    pub fn check_vesting_schedule(&self) -> Result<(), String> {
+        let denominator = self.vesting_schedule_denominator;
+        if denominator == 0 {
+            return Err("Vesting schedule denominator is 0".to_string());
+        }
+        let last_numerator = match self.vesting_schedule_numerators.last() {
+            Some(last_numerator) => *last_numerator,
+            None => return Err("Vesting schedule has no numerators".to_string()),
+        };
+
+        let numerators: u128 = self
+            .vesting_schedule_numerators
+            .iter()
+            .map(|numerator| *numerator as u128)
+            .sum();
+        if numerators > denominator as u128 {
+            return Err(format!(
+                "Numerators {} add up over the denominator {}",
+                numerators, denominator
+            ));
+        }
+        let unvested = denominator as u128 - numerators;
+        if unvested > 0 && last_numerator == 0 {
+            return Err(format!(
+                "Numerators add up to {} of the denominator {} and the last one is 0, so the cliff never ends and the rest never vests",
+                numerators, denominator
+            ));
+        }
+        if unvested > 0 && unvested % last_numerator as u128 != 0 {
+            return Err(format!(
+                "Numerators don't add up to the denominator {} (with the last one {} being repeated)",
+                denominator, last_numerator
+            ));
+        }
+
+        // Fractions are vested as 32 bit fixed point numbers, which can't be non-zero and under
+        // 2^-32
+        if let Some(numerator) = self.vesting_schedule_numerators.iter().find(|numerator| {
+            **numerator != 0 && ((**numerator as u128) << 32) < denominator as u128
+        }) {
+            return Err(format!(
+                "Numerator {} is too small a fraction of the denominator {} to vest",
+                numerator, denominator
+            ));
+        }
+        Ok(())
+    }
+}
+
 impl TryFrom<EmployeePoolConfig> for EmployeePool {
     type Error = anyhow::Error;
 
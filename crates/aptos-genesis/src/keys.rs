@@ -29,6 +29,47 @@ pub struct PublicIdentity {
     pub validator_network_public_key: Option<x25519::PublicKey>,
 }
 
+/// Type for serializing the private key of a single account, used for the per role key files
+#[derive(Deserialize, Serialize)]
+pub struct PrivateAccountKey {
+    pub account_address: AccountAddress,
+    pub account_private_key: Ed25519PrivateKey,
+}
+
+/// Type for serializing the public key of a single account
+///
+/// This is a subset of [`PublicIdentity`], so it can be read as one.
+#[derive(Deserialize, Serialize, Clone)]
+pub struct PublicAccountKey {
+    pub account_address: AccountAddress,
+    pub account_public_key: Ed25519PublicKey,
+}
+
+/// Type for serializing a private consensus key file
+#[derive(Deserialize, Serialize)]
+pub struct PrivateConsensusKey {
+    pub consensus_private_key: bls12381::PrivateKey,
+}
+
+/// Type for serializing a public consensus key file
+#[derive(Deserialize, Serialize, Clone)]
+pub struct PublicConsensusKey {
+    pub consensus_public_key: bls12381::PublicKey,
+    pub consensus_proof_of_possession: bls12381::ProofOfPossession,
+}
+
+/// Type for serializing a private network identity file
+#[derive(Deserialize, Serialize)]
+pub struct PrivateNetworkKey {
+    pub network_private_key: x25519::PrivateKey,
+}
+
+/// Type for serializing a public network identity file
+#[derive(Deserialize, Serialize, Clone)]
+pub struct PublicNetworkKey {
+    pub network_public_key: x25519::PublicKey,
+}
+
 /// Generates objects used for a user in genesis
 pub fn generate_key_objects(
     keygen: &mut KeyGen,
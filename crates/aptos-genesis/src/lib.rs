@@ -129,18 +129,22 @@ impl GenesisInfo {
     }
 
     pub fn generate_waypoint(&mut self) -> anyhow::Result<Waypoint> {
-        let genesis = self.get_genesis();
-        let path = TempPath::new();
-        let aptosdb = AptosDB::open(
-            &path,
-            false,
-            NO_OP_STORAGE_PRUNER_CONFIG,
-            RocksdbConfigs::default(),
-            false,
-            BUFFERED_STATE_TARGET_ITEMS,
-            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
-        )?;
-        let db_rw = DbReaderWriter::new(aptosdb);
-        executor::db_bootstrapper::generate_waypoint::<AptosVM>(&db_rw, genesis)
+        generate_waypoint(self.get_genesis())
     }
 }
+
+/// Generates the waypoint for a genesis transaction by applying it to an empty temporary DB
+pub fn generate_waypoint(genesis: &Transaction) -> anyhow::Result<Waypoint> {
+    let path = TempPath::new();
+    let aptosdb = AptosDB::open(
+        &path,
+        false,
+        NO_OP_STORAGE_PRUNER_CONFIG,
+        RocksdbConfigs::default(),
+        false,
+        BUFFERED_STATE_TARGET_ITEMS,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    )?;
+    let db_rw = DbReaderWriter::new(aptosdb);
+    executor::db_bootstrapper::generate_waypoint::<AptosVM>(&db_rw, genesis)
+}
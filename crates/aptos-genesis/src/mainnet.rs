@@ -2,16 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{builder::GenesisConfiguration, config::ValidatorConfiguration};
-use aptos_config::config::{
-    RocksdbConfigs, BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
-    NO_OP_STORAGE_PRUNER_CONFIG,
-};
-use aptos_temppath::TempPath;
 use aptos_types::{chain_id::ChainId, transaction::Transaction, waypoint::Waypoint};
-use aptos_vm::AptosVM;
-use aptosdb::AptosDB;
 use framework::ReleaseBundle;
-use storage_interface::DbReaderWriter;
 use vm_genesis::{AccountBalance, EmployeePool, ValidatorWithCommissionRate};
 
 /// Holder object for all pieces needed to generate a genesis transaction
@@ -116,18 +108,6 @@ impl MainnetGenesisInfo {
     }
 
     pub fn generate_waypoint(&mut self) -> anyhow::Result<Waypoint> {
-        let genesis = self.get_genesis();
-        let path = TempPath::new();
-        let aptosdb = AptosDB::open(
-            &path,
-            false,
-            NO_OP_STORAGE_PRUNER_CONFIG,
-            RocksdbConfigs::default(),
-            false,
-            BUFFERED_STATE_TARGET_ITEMS,
-            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
-        )?;
-        let db_rw = DbReaderWriter::new(aptosdb);
-        executor::db_bootstrapper::generate_waypoint::<AptosVM>(&db_rw, genesis)
+        crate::generate_waypoint(self.get_genesis())
     }
 }
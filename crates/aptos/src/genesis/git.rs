@@ -1,31 +1,57 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::utils::create_dir_if_not_exist;
+use crate::common::utils::{check_if_file_exists, create_dir_if_not_exist, read_from_file};
 use crate::{
     common::{
-        types::{CliError, CliTypedResult},
+        types::{CliError, CliTypedResult, PromptOptions},
         utils::write_to_file,
     },
     CliCommand,
 };
 use aptos_config::config::Token;
 use aptos_genesis::config::Layout;
-use aptos_github_client::Client as GithubClient;
+use aptos_github_client::{Client as GithubClient, Error as GithubError, URL as GITHUB_API_URL};
+use aptos_logger::warn;
+use aptos_rate_limiter::rate_limit::Bucket;
 use async_trait::async_trait;
 use clap::Parser;
 use framework::ReleaseBundle;
 use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
 use std::path::Path;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
 use std::{fmt::Debug, io::Read, path::PathBuf, str::FromStr};
 
 pub const LAYOUT_FILE: &str = "layout.yaml";
 pub const OPERATOR_FILE: &str = "operator.yaml";
 pub const OWNER_FILE: &str = "owner.yaml";
+/// JSON encoded alternative to the operator file
+pub const OPERATOR_JSON_FILE: &str = "operator.json";
+/// JSON encoded alternative to the owner file
+pub const OWNER_JSON_FILE: &str = "owner.json";
 pub const FRAMEWORK_NAME: &str = "framework.mrb";
 pub const BALANCES_FILE: &str = "balances.yaml";
+/// Directory of balances files, merged in place of the single balances file
+pub const BALANCES_DIR: &str = "balances";
 pub const EMPLOYEE_VESTING_ACCOUNTS_FILE: &str = "employee_vesting_accounts.yaml";
 
+/// Prefix of a `MergeRepos` source that's a Github repository rather than a local directory
+const GITHUB_SOURCE_PREFIX: &str = "github:";
+const DEFAULT_GITHUB_MAX_RETRIES: usize = 3;
+/// Initial backoff after Github rate limits a request, doubled on each retry
+const RATE_LIMITED_BACKOFF_MS: u64 = 1_000;
+/// HTTP status code Github returns when a request is rate limited
+const TOO_MANY_REQUESTS: u16 = 429;
+/// HTTP status code Github returns for bad credentials
+const UNAUTHORIZED: u16 = 401;
+/// HTTP status code Github returns for missing files, and for private repositories that the token
+/// can't see
+const NOT_FOUND: u16 = 404;
+
 /// Setup a shared Git repository for Genesis
 ///
 /// This will setup a folder or an online Github repository to be used
@@ -52,12 +78,230 @@ impl CliCommand<()> for SetupGit {
 
         // Upload layout file to ensure we can read later
         let client = self.git_options.get_client()?;
-        client.put(Path::new(LAYOUT_FILE), &layout)?;
+        let mut changes = GitChanges::default();
+        changes.put(Path::new(LAYOUT_FILE), &layout)?;
+        client.commit(changes, "Setup genesis repository")?;
 
         Ok(())
     }
 }
 
+/// Merge several genesis repositories into one local repository
+///
+/// Working groups can keep e.g. the balances and the validators in separate repositories.  A
+/// file can only come from one source, unless every source that has it has the same contents,
+/// and a user's files can only come from one source.  Sources with a layout file must agree on
+/// everything but the users, which are combined.
+#[derive(Parser)]
+pub struct MergeRepos {
+    /// Repositories to merge, each a local directory or `github:owner/repository@branch`
+    ///
+    /// The branch is optional and defaults to main
+    #[clap(long, required = true, multiple_values = true)]
+    pub(crate) sources: Vec<GitSource>,
+    /// Local directory to write the merged repository to
+    #[clap(long, parse(from_os_str))]
+    pub(crate) output_dir: PathBuf,
+    /// Path to Github API token, for Github sources
+    #[clap(long, parse(from_os_str))]
+    pub(crate) github_token_file: Option<PathBuf>,
+    /// Name of an environment variable holding the Github API token, for Github sources
+    #[clap(long)]
+    pub(crate) github_token_env: Option<String>,
+    /// Github API URL for Github sources, see `--github-api-url` of the other genesis commands
+    #[clap(long)]
+    pub(crate) github_api_url: Option<String>,
+    /// Path to a CA certificate to trust for the Github API, for Github sources
+    #[clap(long, parse(from_os_str))]
+    pub(crate) ca_cert_file: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<Vec<PathBuf>> for MergeRepos {
+    fn command_name(&self) -> &'static str {
+        "MergeRepos"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<PathBuf>> {
+        check_if_file_exists(
+            self.output_dir.join(LAYOUT_FILE).as_path(),
+            self.prompt_options,
+        )?;
+
+        let github_options = GitOptions {
+            github_token_file: self.github_token_file.clone(),
+            github_token_env: self.github_token_env.clone(),
+            github_max_retries: DEFAULT_GITHUB_MAX_RETRIES,
+            github_api_url: self.github_api_url.clone(),
+            ca_cert_file: self.ca_cert_file.clone(),
+            ..Default::default()
+        };
+        let mut sources = Vec::new();
+        for source in self.sources.iter() {
+            let client = source.git_options(&github_options).get_client()?;
+            sources.push((source.to_string(), client));
+        }
+        let changes = merge_repos(&sources)?;
+        let files = changes.files.iter().map(|(name, _)| name.clone()).collect();
+        Client::local(self.output_dir).commit(changes, "Merge genesis repositories")?;
+        Ok(files)
+    }
+}
+
+/// Collects the files of all of the sources, failing on any conflict between them
+fn merge_repos(sources: &[(String, Client)]) -> CliTypedResult<GitChanges> {
+    // Layouts have to match apart from their users
+    let mut layout: Option<(String, Layout)> = None;
+    for (source, client) in sources {
+        let source_layout: Layout = match client.get_optional_bytes(Path::new(LAYOUT_FILE))? {
+            Some(bytes) => from_yaml(&String::from_utf8(bytes)?)?,
+            None => continue,
+        };
+        match layout.as_mut() {
+            Some((layout_source, layout)) => {
+                let users = std::mem::take(&mut layout.users);
+                let same_layout = to_yaml(&Layout {
+                    users: vec![],
+                    ..source_layout.clone()
+                })? == to_yaml(layout)?;
+                layout.users = users;
+                if !same_layout {
+                    return Err(CliError::UnexpectedError(format!(
+                        "Layout files in {} and {} differ in more than their users",
+                        layout_source, source
+                    )));
+                }
+                for user in source_layout.users {
+                    if !layout.users.contains(&user) {
+                        layout.users.push(user);
+                    }
+                }
+            }
+            None => layout = Some((source.clone(), source_layout)),
+        }
+    }
+    let layout = match layout {
+        Some((_, layout)) => layout,
+        None => {
+            return Err(CliError::UnexpectedError(
+                "None of the sources have a layout file".to_string(),
+            ))
+        }
+    };
+
+    let mut files: BTreeMap<PathBuf, (&str, Vec<u8>)> = BTreeMap::new();
+    let mut user_sources: BTreeMap<&str, &str> = BTreeMap::new();
+    for (source, client) in sources {
+        let mut paths = vec![
+            PathBuf::from(FRAMEWORK_NAME),
+            PathBuf::from(BALANCES_FILE),
+            PathBuf::from(EMPLOYEE_VESTING_ACCOUNTS_FILE),
+        ];
+        paths.extend(
+            client
+                .list_files(Path::new(BALANCES_DIR))?
+                .unwrap_or_default(),
+        );
+        for user in layout.users.iter() {
+            let user_dir = PathBuf::from(user);
+            let owner_file = client.config_file(&user_dir, OWNER_FILE, OWNER_JSON_FILE)?;
+            if client.get_optional_bytes(owner_file.as_path())?.is_none() {
+                continue;
+            }
+            if let Some(other_source) = user_sources.insert(user, source) {
+                return Err(CliError::UnexpectedError(format!(
+                    "User {} is defined in both {} and {}",
+                    user, other_source, source
+                )));
+            }
+            paths.push(owner_file);
+            paths.push(client.config_file(&user_dir, OPERATOR_FILE, OPERATOR_JSON_FILE)?);
+        }
+
+        for path in paths {
+            let bytes = match client.get_optional_bytes(path.as_path())? {
+                Some(bytes) => bytes,
+                None => continue,
+            };
+            if let Some((other_source, other_bytes)) = files.get(&path) {
+                if *other_bytes != bytes {
+                    return Err(CliError::UnexpectedError(format!(
+                        "{} differs between {} and {}",
+                        path.display(),
+                        other_source,
+                        source
+                    )));
+                }
+                continue;
+            }
+            files.insert(path, (source.as_str(), bytes));
+        }
+    }
+
+    let mut changes = GitChanges::default();
+    changes.put(Path::new(LAYOUT_FILE), &layout)?;
+    for (path, (_, bytes)) in files {
+        changes.put_bytes(path.as_path(), bytes);
+    }
+    Ok(changes)
+}
+
+/// A repository to read genesis files from, either a local directory or a Github repository
+#[derive(Clone, Debug)]
+pub enum GitSource {
+    Local(PathBuf),
+    Github(GithubRepo, String),
+}
+
+impl GitSource {
+    /// Options to read the source with, taking the Github settings from `github_options`
+    fn git_options(&self, github_options: &GitOptions) -> GitOptions {
+        match self {
+            GitSource::Local(path) => GitOptions {
+                local_repository_dir: Some(path.clone()),
+                ..Default::default()
+            },
+            GitSource::Github(repository, branch) => GitOptions {
+                github_repository: Some(repository.clone()),
+                github_branch: branch.clone(),
+                ..github_options.clone()
+            },
+        }
+    }
+}
+
+impl FromStr for GitSource {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(repository) = s.strip_prefix(GITHUB_SOURCE_PREFIX) {
+            let (repository, branch) = repository.split_once('@').unwrap_or((repository, "main"));
+            Ok(GitSource::Github(
+                GithubRepo::from_str(repository)?,
+                branch.to_string(),
+            ))
+        } else {
+            Ok(GitSource::Local(PathBuf::from(s)))
+        }
+    }
+}
+
+impl Display for GitSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitSource::Local(path) => write!(f, "{}", path.display()),
+            GitSource::Github(repository, branch) => write!(
+                f,
+                "{}{}/{}@{}",
+                GITHUB_SOURCE_PREFIX, repository.owner, repository.repository, branch
+            ),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct GithubRepo {
     owner: String,
@@ -94,64 +338,270 @@ pub struct GitOptions {
     #[clap(long, parse(from_os_str))]
     pub(crate) github_token_file: Option<PathBuf>,
 
+    /// Name of an environment variable holding the Github API token, instead of a token file
+    ///
+    /// The token itself is never printed.  Token must have repo:* permissions
+    #[clap(long)]
+    pub(crate) github_token_env: Option<String>,
+
     /// Path to local git repository
     #[clap(long, parse(from_os_str))]
     pub(crate) local_repository_dir: Option<PathBuf>,
+
+    /// Maximum number of requests per second to make to Github
+    ///
+    /// Requests are unlimited if not set
+    #[clap(long)]
+    pub(crate) github_requests_per_second: Option<usize>,
+
+    /// Number of times to retry a Github request that was rate limited by the server
+    #[clap(long, default_value_t = DEFAULT_GITHUB_MAX_RETRIES)]
+    pub(crate) github_max_retries: usize,
+
+    /// Github API URL, for Github Enterprise Server e.g. `https://github.example.com/api/v3`
+    ///
+    /// A bare Enterprise Server host gets its `/api/v3` prefix added.  Defaults to
+    /// https://api.github.com
+    #[clap(long)]
+    pub(crate) github_api_url: Option<String>,
+
+    /// Path to a PEM or DER encoded CA certificate to trust for the Github API
+    ///
+    /// For Enterprise Server appliances with a private certificate authority
+    #[clap(long, parse(from_os_str))]
+    pub(crate) ca_cert_file: Option<PathBuf>,
 }
 
 impl GitOptions {
+    /// The repository these options read from, if they name exactly one
+    pub fn source(&self) -> Option<GitSource> {
+        match (&self.github_repository, &self.local_repository_dir) {
+            (Some(repository), None) => Some(GitSource::Github(
+                repository.clone(),
+                self.github_branch.clone(),
+            )),
+            (None, Some(path)) => Some(GitSource::Local(path.clone())),
+            _ => None,
+        }
+    }
+
     pub fn get_client(self) -> CliTypedResult<Client> {
-        if self.github_repository.is_none()
-            && self.github_token_file.is_none()
-            && self.local_repository_dir.is_some()
-        {
-            Ok(Client::local(self.local_repository_dir.unwrap()))
-        } else if self.github_repository.is_some()
-            && self.github_token_file.is_some()
-            && self.local_repository_dir.is_none()
-        {
-            Client::github(
-                self.github_repository.unwrap(),
+        let token_source = match (self.github_token_file, self.github_token_env) {
+            (Some(path), None) => Some(GithubTokenSource::File(path)),
+            (None, Some(name)) => Some(GithubTokenSource::Env(name)),
+            (None, None) => None,
+            (Some(_), Some(_)) => {
+                return Err(CliError::CommandArgumentError(
+                    "Only one of --github-token-file and --github-token-env can be provided"
+                        .to_string(),
+                ))
+            }
+        };
+
+        match (
+            self.github_repository,
+            token_source,
+            self.local_repository_dir,
+        ) {
+            (None, None, Some(local_repository_dir)) => Ok(Client::local(local_repository_dir)),
+            (Some(github_repository), Some(token_source), None) => Client::github(
+                github_repository,
                 self.github_branch,
-                self.github_token_file.unwrap(),
-            )
-        } else {
-            Err(CliError::CommandArgumentError("Must provide either only --local-repository-dir or both --github-repository and --github-token-path".to_string()))
+                token_source,
+                self.github_requests_per_second,
+                self.github_max_retries,
+                self.github_api_url.as_deref(),
+                self.ca_cert_file.as_deref(),
+            ),
+            _ => Err(CliError::CommandArgumentError("Must provide either only --local-repository-dir or both --github-repository and one of --github-token-file or --github-token-env".to_string())),
+        }
+    }
+}
+
+/// Normalizes a Github API URL, adding the `/api/v3` prefix to a bare Enterprise Server host
+fn github_api_url(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    let host = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    if url == GITHUB_API_URL || host.contains('/') {
+        url.to_string()
+    } else {
+        format!("{}/api/v3", url)
+    }
+}
+
+/// Where the Github token is read from, kept so that a rotated token can be re-read
+#[derive(Clone, Debug)]
+pub enum GithubTokenSource {
+    File(PathBuf),
+    /// Name of the environment variable holding the token
+    Env(String),
+}
+
+impl GithubTokenSource {
+    pub fn read_token(&self) -> CliTypedResult<String> {
+        match self {
+            GithubTokenSource::File(path) => Ok(Token::FromDisk(path.clone()).read_token()?),
+            GithubTokenSource::Env(name) => std::env::var(name)
+                .map(|token| token.trim().to_string())
+                .map_err(|_| {
+                    CliError::CommandArgumentError(format!(
+                        "Environment variable {} for the Github token is not set",
+                        name
+                    ))
+                }),
+        }
+    }
+}
+
+impl Display for GithubTokenSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GithubTokenSource::File(path) => write!(f, "token file {}", path.display()),
+            GithubTokenSource::Env(name) => write!(f, "environment variable {}", name),
         }
     }
 }
 
 /// A client for abstracting away local vs Github storage
 ///
+/// The SHA-256 hash of every file read is recorded, so that it's known exactly what genesis was
+/// built from.
+///
 /// Note: Writes do not commit locally
-pub enum Client {
+pub struct Client {
+    storage: Storage,
+    reads: Mutex<BTreeMap<PathBuf, String>>,
+}
+
+/// Where a [`Client`] keeps its files
+pub enum Storage {
     Local(PathBuf),
-    Github(GithubClient),
+    Github(RateLimitedGithubClient),
 }
 
 impl Client {
+    pub fn new(storage: Storage) -> Client {
+        Client {
+            storage,
+            reads: Mutex::new(BTreeMap::new()),
+        }
+    }
+
     pub fn local(path: PathBuf) -> Client {
-        Client::Local(path)
+        Client::new(Storage::Local(path))
     }
 
     pub fn github(
         repository: GithubRepo,
         branch: String,
-        token_path: PathBuf,
+        token_source: GithubTokenSource,
+        requests_per_second: Option<usize>,
+        max_retries: usize,
+        api_url: Option<&str>,
+        ca_cert_file: Option<&Path>,
     ) -> CliTypedResult<Client> {
-        let token = Token::FromDisk(token_path).read_token()?;
-        Ok(Client::Github(GithubClient::new(
+        let token = token_source.read_token()?;
+        let mut client = GithubClient::new_with_url(
+            api_url
+                .map(github_api_url)
+                .unwrap_or_else(|| GITHUB_API_URL.to_string()),
             repository.owner,
             repository.repository,
             branch,
             token,
-        )))
+        );
+        if let Some(ca_cert_file) = ca_cert_file {
+            client
+                .set_ca_certificate(&read_from_file(ca_cert_file)?)
+                .map_err(|err| {
+                    CliError::CommandArgumentError(format!(
+                        "Failed to use CA certificate {}: {}",
+                        ca_cert_file.display(),
+                        err
+                    ))
+                })?;
+        }
+        Ok(Client::new(Storage::Github(RateLimitedGithubClient::new(
+            client,
+            requests_per_second,
+            max_retries,
+            Some(token_source),
+        ))))
     }
 
-    /// Retrieves an object as a YAML encoded file from the appropriate storage
+    /// SHA-256 hashes of the files read so far, hex encoded and keyed by path
+    pub fn reads(&self) -> BTreeMap<PathBuf, String> {
+        self.reads.lock().unwrap().clone()
+    }
+
+    /// The commit at the head of the branch, or `None` for a local repository
+    pub fn head_commit(&self) -> CliTypedResult<Option<String>> {
+        match &self.storage {
+            Storage::Local(_) => Ok(None),
+            Storage::Github(client) => client.get_branch_commit().map(Some),
+        }
+    }
+
+    fn record_read(&self, path: &Path, contents: &[u8]) {
+        self.reads
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), file_hash(contents));
+    }
+
+    /// Retrieves an object from the appropriate storage
+    ///
+    /// Files with a `.json` extension are JSON encoded, and any others are YAML encoded.
     pub fn get<T: DeserializeOwned + Debug>(&self, path: &Path) -> CliTypedResult<T> {
-        match self {
-            Client::Local(local_repository_path) => {
+        let contents = String::from_utf8(self.get_bytes(path)?)?;
+        if path
+            .extension()
+            .map_or(false, |extension| extension == "json")
+        {
+            from_json(&contents)
+        } else {
+            from_yaml(&contents)
+        }
+    }
+
+    /// Path of a configuration file in `dir` that can be either YAML or JSON encoded
+    ///
+    /// YAML files can also use the `.yml` extension.  This is the `.yaml` file if none exist, so
+    /// that a missing file is reported by its usual name.
+    pub fn config_file(
+        &self,
+        dir: &Path,
+        yaml_file: &str,
+        json_file: &str,
+    ) -> CliTypedResult<PathBuf> {
+        let yaml_file = dir.join(yaml_file);
+        let candidates = [
+            yaml_file.clone(),
+            yaml_file.with_extension("yml"),
+            dir.join(json_file),
+        ];
+        let files = self.list_files(dir)?.unwrap_or_default();
+        let mut existing: Vec<_> = candidates
+            .into_iter()
+            .filter(|file| files.contains(file))
+            .collect();
+        if existing.len() > 1 {
+            return Err(CliError::CommandArgumentError(format!(
+                "{} exist, only one of them can be used",
+                existing
+                    .iter()
+                    .map(|file| file.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" and ")
+            )));
+        }
+        Ok(existing.pop().unwrap_or(yaml_file))
+    }
+
+    /// Retrieves the raw contents of a file from the appropriate storage
+    pub fn get_bytes(&self, path: &Path) -> CliTypedResult<Vec<u8>> {
+        let contents = match &self.storage {
+            Storage::Local(local_repository_path) => {
                 let path = local_repository_path.join(path);
 
                 if !path.exists() {
@@ -164,21 +614,85 @@ impl Client {
                 let mut file = std::fs::File::open(path.as_path())
                     .map_err(|e| CliError::IO(path.display().to_string(), e))?;
 
-                let mut contents = String::new();
-                file.read_to_string(&mut contents)
+                let mut contents = Vec::new();
+                file.read_to_end(&mut contents)
                     .map_err(|e| CliError::IO(path.display().to_string(), e))?;
-                from_yaml(&contents)
+                contents
             }
-            Client::Github(client) => {
-                from_base64_encoded_yaml(&client.get_file(&path.display().to_string())?)
+            Storage::Github(client) => {
+                base64::decode(client.get_file(&path.display().to_string())?)?
+            }
+        };
+        self.record_read(path, &contents);
+        Ok(contents)
+    }
+
+    /// Retrieves the raw contents of a file, or `None` if it doesn't exist
+    pub fn get_optional_bytes(&self, path: &Path) -> CliTypedResult<Option<Vec<u8>>> {
+        match &self.storage {
+            Storage::Local(local_repository_path) => {
+                if local_repository_path.join(path).exists() {
+                    self.get_bytes(path).map(Some)
+                } else {
+                    Ok(None)
+                }
+            }
+            Storage::Github(client) => {
+                match client.get_optional_file(&path.display().to_string())? {
+                    Some(contents) => {
+                        let contents = base64::decode(contents)?;
+                        self.record_read(path, &contents);
+                        Ok(Some(contents))
+                    }
+                    None => Ok(None),
+                }
             }
         }
     }
 
+    /// Lists the files in a directory, sorted by path, or `None` if the directory doesn't exist
+    ///
+    /// Paths are relative to the repository, and subdirectories are skipped.
+    pub fn list_files(&self, dir: &Path) -> CliTypedResult<Option<Vec<PathBuf>>> {
+        let mut files = match &self.storage {
+            Storage::Local(local_repository_path) => {
+                let path = local_repository_path.join(dir);
+                if !path.is_dir() {
+                    return Ok(None);
+                }
+
+                let mut files = Vec::new();
+                let entries = std::fs::read_dir(path.as_path())
+                    .map_err(|e| CliError::IO(path.display().to_string(), e))?;
+                for entry in entries {
+                    let entry = entry.map_err(|e| CliError::IO(path.display().to_string(), e))?;
+                    if entry.path().is_file() {
+                        files.push(dir.join(entry.file_name()));
+                    }
+                }
+                files
+            }
+            Storage::Github(client) => {
+                if let Some(entries) = client.get_directory(&dir.display().to_string())? {
+                    // Github marks directories with a trailing slash
+                    entries
+                        .into_iter()
+                        .filter(|entry| !entry.ends_with('/'))
+                        .map(PathBuf::from)
+                        .collect()
+                } else {
+                    return Ok(None);
+                }
+            }
+        };
+        files.sort();
+        Ok(Some(files))
+    }
+
     /// Puts an object as a YAML encoded file to the appropriate storage
     pub fn put<T: Serialize + ?Sized>(&self, name: &Path, input: &T) -> CliTypedResult<()> {
-        match self {
-            Client::Local(local_repository_path) => {
+        match &self.storage {
+            Storage::Local(local_repository_path) => {
                 let path = local_repository_path.join(name);
 
                 // Create repository path and any sub-directories
@@ -196,7 +710,7 @@ impl Client {
                     to_yaml(input)?.as_bytes(),
                 )?;
             }
-            Client::Github(client) => {
+            Storage::Github(client) => {
                 client.put(&name.display().to_string(), &to_base64_encoded_yaml(input)?)?;
             }
         }
@@ -204,13 +718,59 @@ impl Client {
         Ok(())
     }
 
+    /// Writes all of the staged files in a single commit, or none of them
+    ///
+    /// A local repository has no commits, so if a write fails, the files already written are
+    /// restored to what they were before.
+    pub fn commit(&self, changes: GitChanges, message: &str) -> CliTypedResult<()> {
+        match &self.storage {
+            Storage::Local(local_repository_path) => {
+                let mut written: Vec<(PathBuf, Option<Vec<u8>>)> = Vec::new();
+                for (name, bytes) in changes.files {
+                    let path = local_repository_path.join(&name);
+                    let previous = std::fs::read(&path).ok();
+                    let result = path
+                        .parent()
+                        .map(create_dir_if_not_exist)
+                        .unwrap_or(Ok(()))
+                        .and_then(|_| {
+                            write_to_file(path.as_path(), &path.display().to_string(), &bytes)
+                        });
+                    if let Err(err) = result {
+                        for (path, previous) in written.into_iter().rev() {
+                            let _ = match previous {
+                                Some(previous) => std::fs::write(path, previous),
+                                None => std::fs::remove_file(path),
+                            };
+                        }
+                        return Err(CliError::UnexpectedError(format!(
+                            "Failed to commit '{}', no files were changed: {}",
+                            message, err
+                        )));
+                    }
+                    written.push((path, previous));
+                }
+            }
+            Storage::Github(client) => {
+                let files: Vec<_> = changes
+                    .files
+                    .into_iter()
+                    .map(|(name, bytes)| (name.display().to_string(), bytes))
+                    .collect();
+                client.put_files(&files, message)?;
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn create_dir(&self, dir: &Path) -> CliTypedResult<()> {
-        match self {
-            Client::Local(local_repository_path) => {
+        match &self.storage {
+            Storage::Local(local_repository_path) => {
                 let path = local_repository_path.join(dir);
                 create_dir_if_not_exist(path.as_path())?;
             }
-            Client::Github(_) => {
+            Storage::Github(_) => {
                 // There's no such thing as an empty directory in Git, so do nothing
             }
         }
@@ -220,23 +780,243 @@ impl Client {
 
     /// Retrieve framework release bundle.
     pub fn get_framework(&self) -> CliTypedResult<ReleaseBundle> {
-        match self {
-            Client::Local(local_repository_path) => {
-                let path = local_repository_path.join(FRAMEWORK_NAME);
-                if !path.exists() {
-                    return Err(CliError::UnableToReadFile(
-                        path.display().to_string(),
-                        "File not found".to_string(),
-                    ));
+        let bytes = self.get_bytes(Path::new(FRAMEWORK_NAME))?;
+        Ok(bcs::from_bytes::<ReleaseBundle>(&bytes)?)
+    }
+}
+
+/// Files staged to be written together with `Client::commit`
+#[derive(Default)]
+pub struct GitChanges {
+    files: Vec<(PathBuf, Vec<u8>)>,
+}
+
+impl GitChanges {
+    /// Stages an object as a YAML encoded file
+    pub fn put<T: Serialize + ?Sized>(&mut self, name: &Path, input: &T) -> CliTypedResult<()> {
+        self.put_bytes(name, to_yaml(input)?.into_bytes());
+        Ok(())
+    }
+
+    /// Stages a file's raw contents
+    pub fn put_bytes(&mut self, name: &Path, bytes: Vec<u8>) {
+        self.files.push((name.to_path_buf(), bytes));
+    }
+}
+
+/// A Github client that throttles requests to a maximum rate, and retries requests that the
+/// server rate limited
+///
+/// If the server rejects the token, it's re-read once from its source in case it was rotated.
+pub struct RateLimitedGithubClient {
+    client: RwLock<GithubClient>,
+    bucket: Option<Mutex<Bucket>>,
+    max_retries: usize,
+    token_source: Option<GithubTokenSource>,
+}
+
+impl RateLimitedGithubClient {
+    pub fn new(
+        client: GithubClient,
+        requests_per_second: Option<usize>,
+        max_retries: usize,
+        token_source: Option<GithubTokenSource>,
+    ) -> RateLimitedGithubClient {
+        // Allow up to a second's worth of requests to burst, and then refill at the given rate
+        let bucket = requests_per_second.filter(|rate| *rate > 0).map(|rate| {
+            Mutex::new(Bucket::new(
+                "github".to_string(),
+                String::new(),
+                String::new(),
+                rate,
+                rate,
+                rate,
+                None,
+            ))
+        });
+        RateLimitedGithubClient {
+            client: RwLock::new(client),
+            bucket,
+            max_retries,
+            token_source,
+        }
+    }
+
+    pub fn get_file(&self, path: &str) -> CliTypedResult<String> {
+        self.request(|client| client.get_file(path))
+            .map_err(|err| self.explain_error(err))
+    }
+
+    pub fn put(&self, path: &str, content: &str) -> CliTypedResult<()> {
+        self.request(|client| client.put(path, content))
+            .map_err(|err| self.explain_error(err))
+    }
+
+    pub fn put_files(&self, files: &[(String, Vec<u8>)], message: &str) -> CliTypedResult<()> {
+        self.request(|client| client.put_files(files, message))
+            .map_err(|err| self.explain_error(err))
+    }
+
+    pub fn get_branch_commit(&self) -> CliTypedResult<String> {
+        self.request(|client| client.get_branch_commit())
+            .map_err(|err| self.explain_error(err))
+    }
+
+    /// Gets a file's contents, or `None` if it doesn't exist
+    pub fn get_optional_file(&self, path: &str) -> CliTypedResult<Option<String>> {
+        match self.request(|client| client.get_file(path)) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(GithubError::NotFound(_)) => Ok(None),
+            Err(err) => Err(self.explain_error(err)),
+        }
+    }
+
+    /// Lists the paths in a directory, or `None` if it doesn't exist
+    pub fn get_directory(&self, path: &str) -> CliTypedResult<Option<Vec<String>>> {
+        match self.request(|client| client.get_directory(path)) {
+            Ok(entries) => Ok(Some(entries)),
+            Err(GithubError::NotFound(_)) => Ok(None),
+            Err(err) => Err(self.explain_error(err)),
+        }
+    }
+
+    /// Runs a request under the rate limit, retrying with exponential backoff if the server
+    /// rate limited it, and retrying once with a re-read token if the token was rejected
+    fn request<T, F: Fn(&GithubClient) -> Result<T, GithubError>>(
+        &self,
+        operation: F,
+    ) -> Result<T, GithubError> {
+        let mut backoff = Duration::from_millis(RATE_LIMITED_BACKOFF_MS);
+        let mut retries = 0;
+        let mut refreshed_token = false;
+        loop {
+            self.throttle();
+            let result = operation(&self.client.read().unwrap());
+            match result {
+                Err(GithubError::HttpError(TOO_MANY_REQUESTS, _, _))
+                    if retries < self.max_retries =>
+                {
+                    retries += 1;
+                    warn!(
+                        "Github rate limited the request, retrying in {}ms ({}/{})",
+                        backoff.as_millis(),
+                        retries,
+                        self.max_retries
+                    );
+                    std::thread::sleep(backoff);
+                    backoff *= 2;
                 }
-                Ok(ReleaseBundle::read(path)?)
+                Err(err @ GithubError::HttpError(UNAUTHORIZED, _, _)) if !refreshed_token => {
+                    // The token may have expired and been rotated since it was read
+                    let token_source = if let Some(ref token_source) = self.token_source {
+                        token_source
+                    } else {
+                        return Err(err);
+                    };
+                    refreshed_token = true;
+                    warn!(
+                        "Github rejected the token, re-reading it from {} and retrying",
+                        token_source
+                    );
+                    match token_source.read_token() {
+                        Ok(token) => self.client.write().unwrap().set_token(token),
+                        Err(read_err) => {
+                            warn!(
+                                "Failed to re-read the Github token from {}: {}",
+                                token_source, read_err
+                            );
+                            return Err(err);
+                        }
+                    }
+                }
+                result => return result,
             }
-            Client::Github(client) => {
-                let bytes = base64::decode(client.get_file(FRAMEWORK_NAME)?)?;
-                Ok(bcs::from_bytes::<ReleaseBundle>(&bytes)?)
+        }
+    }
+
+    /// Converts a failed request into a [`CliError`], telling apart bad credentials from a
+    /// repository that the token can't see
+    fn explain_error(&self, err: GithubError) -> CliError {
+        match err {
+            GithubError::HttpError(UNAUTHORIZED, _, _) => self.bad_credentials_error(),
+            GithubError::NotFound(_) | GithubError::HttpError(NOT_FOUND, _, _) => {
+                // Github returns 404 for private repositories that the token can't see, so
+                // check whether the repository itself is visible
+                self.throttle();
+                match self.client.read().unwrap().get_repository() {
+                    Err(GithubError::NotFound(repository)) => {
+                        CliError::CommandArgumentError(format!(
+                            "Github repository {} is not visible at {} with the {}.  Either it doesn't exist, or it's private and the token doesn't have access to it",
+                            repository,
+                            self.api_url(),
+                            self.token_description()
+                        ))
+                    }
+                    Err(GithubError::HttpError(UNAUTHORIZED, _, _)) => {
+                        self.bad_credentials_error()
+                    }
+                    _ => self.api_error(err),
+                }
             }
+            err => self.api_error(err),
+        }
+    }
+
+    fn bad_credentials_error(&self) -> CliError {
+        CliError::CommandArgumentError(format!(
+            "Github at {} rejected the {} as bad credentials.  Check that the token is valid and hasn't expired",
+            self.api_url(),
+            self.token_description()
+        ))
+    }
+
+    /// Any other failure, with the API URL so that a misconfigured one is obvious
+    fn api_error(&self, err: GithubError) -> CliError {
+        CliError::UnexpectedError(format!(
+            "Github request to {} failed: {}",
+            self.api_url(),
+            err
+        ))
+    }
+
+    fn api_url(&self) -> String {
+        self.client.read().unwrap().url().to_string()
+    }
+
+    fn token_description(&self) -> String {
+        if let Some(ref token_source) = self.token_source {
+            format!("token from {}", token_source)
+        } else {
+            "token".to_string()
         }
     }
+
+    /// Blocks until the rate limit allows another request
+    fn throttle(&self) {
+        let bucket = if let Some(ref bucket) = self.bucket {
+            bucket
+        } else {
+            return;
+        };
+
+        loop {
+            // Note: The lock must be dropped before sleeping
+            let wait_until = match bucket.lock().unwrap().acquire_all_tokens(1) {
+                Ok(()) => return,
+                Err(wait_until) => wait_until,
+            };
+            let delay = wait_until
+                .map(|wait_until| wait_until.saturating_duration_since(Instant::now()))
+                .unwrap_or_else(|| Duration::from_secs(1));
+            warn!("Throttling Github requests for {}ms", delay.as_millis());
+            std::thread::sleep(delay);
+        }
+    }
+}
+
+/// SHA-256 hex of a file's contents, as recorded in the genesis manifest
+pub fn file_hash(contents: &[u8]) -> String {
+    hex::encode(Sha256::digest(contents))
 }
 
 pub fn to_yaml<T: Serialize + ?Sized>(input: &T) -> CliTypedResult<String> {
@@ -247,6 +1027,10 @@ pub fn from_yaml<T: DeserializeOwned>(input: &str) -> CliTypedResult<T> {
     Ok(serde_yaml::from_str(input)?)
 }
 
+pub fn from_json<T: DeserializeOwned>(input: &str) -> CliTypedResult<T> {
+    serde_json::from_str(input).map_err(|err| CliError::UnexpectedError(err.to_string()))
+}
+
 pub fn to_base64_encoded_yaml<T: Serialize + ?Sized>(input: &T) -> CliTypedResult<String> {
     Ok(base64::encode(to_yaml(input)?))
 }
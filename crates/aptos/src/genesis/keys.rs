@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::common::types::OptionalPoolAddressArgs;
-use crate::common::utils::{create_dir_if_not_exist, current_dir, dir_default_to_current};
+use crate::common::utils::{
+    create_dir_if_not_exist, current_dir, dir_default_to_current, write_to_file,
+};
 use crate::genesis::git::{LAYOUT_FILE, OPERATOR_FILE, OWNER_FILE};
 use crate::governance::CompileScriptFunction;
 use crate::{
@@ -10,23 +12,63 @@ use crate::{
         types::{CliError, CliTypedResult, PromptOptions, RngArgs},
         utils::{check_if_file_exists, read_from_file, write_to_user_only_file},
     },
-    genesis::git::{from_yaml, to_yaml, GitOptions},
+    genesis::git::{from_yaml, to_yaml, GitChanges, GitOptions},
     CliCommand,
 };
-use aptos_genesis::config::{Layout, OperatorConfiguration, OwnerConfiguration};
-use aptos_genesis::keys::PublicIdentity;
+use aptos_crypto::PrivateKey;
+use aptos_genesis::config::{
+    EmployeePoolMap, Layout, LayoutCheck, OperatorConfiguration, OwnerConfiguration,
+};
+use aptos_genesis::keys::{
+    PrivateAccountKey, PrivateConsensusKey, PrivateIdentity, PrivateNetworkKey, PublicAccountKey,
+    PublicConsensusKey, PublicIdentity, PublicNetworkKey,
+};
 use aptos_genesis::{config::HostAndPort, keys::generate_key_objects};
-use aptos_types::account_address::AccountAddress;
-use aptos_types::transaction::{Script, Transaction, WriteSetPayload};
+use aptos_types::account_address::{
+    create_vesting_contract_address, default_stake_pool_address, AccountAddress,
+};
+use aptos_types::transaction::{
+    authenticator::AuthenticationKey, Script, Transaction, WriteSetPayload,
+};
 use async_trait::async_trait;
+use cached_packages::aptos_stdlib;
 use clap::Parser;
-use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-const PRIVATE_KEYS_FILE: &str = "private-keys.yaml";
+pub const PRIVATE_KEYS_FILE: &str = "private-keys.yaml";
 pub const PUBLIC_KEYS_FILE: &str = "public-keys.yaml";
 const VALIDATOR_FILE: &str = "validator-identity.yaml";
 const VFN_FILE: &str = "validator-full-node-identity.yaml";
 
+pub const OWNER_ROLE: &str = "owner";
+pub const VOTER_ROLE: &str = "voter";
+pub const CONSENSUS_ROLE: &str = "consensus";
+pub const VALIDATOR_NETWORK_ROLE: &str = "validator-network";
+pub const FULL_NODE_NETWORK_ROLE: &str = "full-node-network";
+/// Roles that get their own key files with `--split-role-files`
+const ROLES: [&str; 5] = [
+    OWNER_ROLE,
+    VOTER_ROLE,
+    CONSENSUS_ROLE,
+    VALIDATOR_NETWORK_ROLE,
+    FULL_NODE_NETWORK_ROLE,
+];
+
+pub fn role_private_key_file(role: &str) -> String {
+    format!("{}-private-key.yaml", role)
+}
+
+pub fn role_public_key_file(role: &str) -> String {
+    format!("{}-public-key.yaml", role)
+}
+
 /// Generate account key, consensus key, and network key for a validator
 #[derive(Parser)]
 pub struct GenerateKeys {
@@ -34,6 +76,20 @@ pub struct GenerateKeys {
     #[clap(long, parse(from_os_str))]
     pub(crate) output_dir: Option<PathBuf>,
 
+    /// Whether to also write a separate key file for each role
+    ///
+    /// This writes a private and public key file for each of the owner, voter, consensus,
+    /// validator network, and full node network keys, so that each can be handed to whoever
+    /// needs it.  The voter gets its own account key, which is only used if its public key file
+    /// is passed as the voter identity, as the owner is the voter by default.
+    #[clap(long)]
+    pub(crate) split_role_files: bool,
+    /// Address of the operator, used to derive the stake pool address
+    ///
+    /// Defaults to the generated account, as the owner is the operator by default
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) operator_address: Option<AccountAddress>,
+
     #[clap(flatten)]
     pub(crate) pool_address_args: OptionalPoolAddressArgs,
     #[clap(flatten)]
@@ -42,6 +98,15 @@ pub struct GenerateKeys {
     pub rng_args: RngArgs,
 }
 
+impl GenerateKeys {
+    /// Stake pool address for the given owner, unless one was explicitly provided
+    pub(crate) fn pool_address(&self, owner: AccountAddress) -> AccountAddress {
+        self.pool_address_args.pool_address.unwrap_or_else(|| {
+            default_stake_pool_address(owner, self.operator_address.unwrap_or(owner))
+        })
+    }
+}
+
 #[async_trait]
 impl CliCommand<Vec<PathBuf>> for GenerateKeys {
     fn command_name(&self) -> &'static str {
@@ -59,6 +124,18 @@ impl CliCommand<Vec<PathBuf>> for GenerateKeys {
         check_if_file_exists(public_keys_file.as_path(), self.prompt_options)?;
         check_if_file_exists(validator_file.as_path(), self.prompt_options)?;
         check_if_file_exists(vfn_file.as_path(), self.prompt_options)?;
+        if self.split_role_files {
+            for role in ROLES {
+                check_if_file_exists(
+                    output_dir.join(role_private_key_file(role)).as_path(),
+                    self.prompt_options,
+                )?;
+                check_if_file_exists(
+                    output_dir.join(role_public_key_file(role)).as_path(),
+                    self.prompt_options,
+                )?;
+            }
+        }
 
         let mut key_generator = self.rng_args.key_generator()?;
         let (mut validator_blob, mut vfn_blob, private_identity, public_identity) =
@@ -89,15 +166,129 @@ impl CliCommand<Vec<PathBuf>> for GenerateKeys {
             to_yaml(&validator_blob)?.as_bytes(),
         )?;
         write_to_user_only_file(vfn_file.as_path(), VFN_FILE, to_yaml(&vfn_blob)?.as_bytes())?;
-        Ok(vec![
+        let mut files = vec![
             public_keys_file,
             private_keys_file,
             validator_file,
             vfn_file,
-        ])
+        ];
+
+        if self.split_role_files {
+            // Private keys can't be cloned, so split up the aggregate now that it's written
+            let PrivateIdentity {
+                account_address,
+                account_private_key,
+                consensus_private_key,
+                full_node_network_private_key,
+                validator_network_private_key,
+            } = private_identity;
+            let account_key = PrivateAccountKey {
+                account_address,
+                account_private_key,
+            };
+            let public_account_key = PublicAccountKey {
+                account_address: public_identity.account_address,
+                account_public_key: public_identity.account_public_key.clone(),
+            };
+            // Generated after the others, so the aggregate keys don't change with the flag
+            let voter_private_key = key_generator.generate_ed25519_private_key();
+            let voter_public_key = voter_private_key.public_key();
+            let voter_address = AuthenticationKey::ed25519(&voter_public_key).derived_address();
+            let voter_key = PrivateAccountKey {
+                account_address: voter_address,
+                account_private_key: voter_private_key,
+            };
+            let public_voter_key = PublicAccountKey {
+                account_address: voter_address,
+                account_public_key: voter_public_key,
+            };
+            let consensus_key = PrivateConsensusKey {
+                consensus_private_key,
+            };
+            let public_consensus_key = PublicConsensusKey {
+                consensus_public_key: public_identity.consensus_public_key.clone().unwrap(),
+                consensus_proof_of_possession: public_identity
+                    .consensus_proof_of_possession
+                    .clone()
+                    .unwrap(),
+            };
+            let validator_network_key = PrivateNetworkKey {
+                network_private_key: validator_network_private_key,
+            };
+            let public_validator_network_key = PublicNetworkKey {
+                network_public_key: public_identity.validator_network_public_key.unwrap(),
+            };
+            let full_node_network_key = PrivateNetworkKey {
+                network_private_key: full_node_network_private_key,
+            };
+            let public_full_node_network_key = PublicNetworkKey {
+                network_public_key: public_identity.full_node_network_public_key.unwrap(),
+            };
+
+            files.extend(write_role_key_files(
+                output_dir.as_path(),
+                OWNER_ROLE,
+                &account_key,
+                &public_account_key,
+            )?);
+            files.extend(write_role_key_files(
+                output_dir.as_path(),
+                VOTER_ROLE,
+                &voter_key,
+                &public_voter_key,
+            )?);
+            files.extend(write_role_key_files(
+                output_dir.as_path(),
+                CONSENSUS_ROLE,
+                &consensus_key,
+                &public_consensus_key,
+            )?);
+            files.extend(write_role_key_files(
+                output_dir.as_path(),
+                VALIDATOR_NETWORK_ROLE,
+                &validator_network_key,
+                &public_validator_network_key,
+            )?);
+            files.extend(write_role_key_files(
+                output_dir.as_path(),
+                FULL_NODE_NETWORK_ROLE,
+                &full_node_network_key,
+                &public_full_node_network_key,
+            )?);
+        }
+
+        // Let operators know the pool address ahead of time e.g. for monitoring
+        eprintln!(
+            "Stake pool address: {}",
+            self.pool_address(public_identity.account_address)
+        );
+
+        Ok(files)
     }
 }
 
+/// Writes the private and public key files for a single role
+fn write_role_key_files<Private: Serialize, Public: Serialize>(
+    output_dir: &Path,
+    role: &str,
+    private_key: &Private,
+    public_key: &Public,
+) -> CliTypedResult<[PathBuf; 2]> {
+    let private_key_file = output_dir.join(role_private_key_file(role));
+    let public_key_file = output_dir.join(role_public_key_file(role));
+    write_to_user_only_file(
+        private_key_file.as_path(),
+        &role_private_key_file(role),
+        to_yaml(private_key)?.as_bytes(),
+    )?;
+    write_to_user_only_file(
+        public_key_file.as_path(),
+        &role_public_key_file(role),
+        to_yaml(public_key)?.as_bytes(),
+    )?;
+    Ok([public_key_file, private_key_file])
+}
+
 /// Set validator configuration for a single validator in the git repository
 #[derive(Parser)]
 pub struct SetValidatorConfiguration {
@@ -113,6 +304,13 @@ pub struct SetValidatorConfiguration {
     #[clap(long)]
     pub(crate) full_node_host: Option<HostAndPort>,
 
+    /// Whether to keep the validator host out of the seed peers and summary generated with genesis
+    ///
+    /// The validator host is still added on-chain, and a full node host is required so that
+    /// others can connect to the validator's full node instead
+    #[clap(long)]
+    pub(crate) private_validator_address: bool,
+
     /// Stake amount for stake distribution
     #[clap(long, default_value_t = 1)]
     pub(crate) stake_amount: u64,
@@ -148,6 +346,12 @@ impl CliCommand<()> for SetValidatorConfiguration {
     }
 
     async fn execute(self) -> CliTypedResult<()> {
+        if self.private_validator_address && self.full_node_host.is_none() {
+            return Err(CliError::CommandArgumentError(
+                "--full-node-host is required when using --private-validator-address".to_string(),
+            ));
+        }
+
         // Load owner
         let owner_keys_file = if let Some(owner_keys_file) = self.owner_public_identity_file {
             owner_keys_file
@@ -217,22 +421,23 @@ impl CliCommand<()> for SetValidatorConfiguration {
         // Build operator configuration file
         let operator_config = OperatorConfiguration {
             operator_account_address: operator_identity.account_address,
-            operator_account_public_key: operator_identity.account_public_key.clone(),
+            operator_account_public_key: operator_identity.account_public_key.clone().into(),
             consensus_public_key,
             consensus_proof_of_possession,
             validator_network_public_key,
             validator_host: self.validator_host,
             full_node_network_public_key,
             full_node_host: self.full_node_host,
+            publish_validator_address: !self.private_validator_address,
         };
 
         let owner_config = OwnerConfiguration {
             owner_account_address: owner_identity.account_address,
-            owner_account_public_key: owner_identity.account_public_key,
+            owner_account_public_key: owner_identity.account_public_key.into(),
             voter_account_address: voter_identity.account_address,
-            voter_account_public_key: voter_identity.account_public_key,
+            voter_account_public_key: voter_identity.account_public_key.into(),
             operator_account_address: operator_identity.account_address,
-            operator_account_public_key: operator_identity.account_public_key,
+            operator_account_public_key: operator_identity.account_public_key.into(),
             stake_amount: self.stake_amount,
             commission_percentage: self.commission_percentage,
             join_during_genesis: self.join_during_genesis,
@@ -242,9 +447,13 @@ impl CliCommand<()> for SetValidatorConfiguration {
         let operator_file = directory.join(OPERATOR_FILE);
         let owner_file = directory.join(OWNER_FILE);
 
-        let git_client = self.git_options.get_client()?;
-        git_client.put(operator_file.as_path(), &operator_config)?;
-        git_client.put(owner_file.as_path(), &owner_config)
+        let mut changes = GitChanges::default();
+        changes.put(operator_file.as_path(), &operator_config)?;
+        changes.put(owner_file.as_path(), &owner_config)?;
+        self.git_options.get_client()?.commit(
+            changes,
+            &format!("Set validator configuration for {}", self.username),
+        )
     }
 }
 
@@ -263,6 +472,12 @@ pub struct GenerateLayoutTemplate {
     #[clap(long, parse(from_os_str), default_value = LAYOUT_FILE)]
     pub(crate) output_file: PathBuf,
 
+    /// Whether to prompt for each value in the layout, rather than writing the defaults
+    ///
+    /// Each value is checked as it's entered.  This must be run from an interactive terminal.
+    #[clap(long)]
+    pub(crate) interactive: bool,
+
     #[clap(flatten)]
     pub(crate) prompt_options: PromptOptions,
 }
@@ -275,7 +490,17 @@ impl CliCommand<()> for GenerateLayoutTemplate {
 
     async fn execute(self) -> CliTypedResult<()> {
         check_if_file_exists(self.output_file.as_path(), self.prompt_options)?;
-        let layout = Layout::default();
+        let layout = if self.interactive {
+            if !atty::is(atty::Stream::Stdin) {
+                return Err(CliError::CommandArgumentError(
+                    "--interactive requires an interactive terminal, drop the flag to generate a template with default values".to_string(),
+                ));
+            }
+            let stdin = std::io::stdin();
+            prompt_layout(&mut stdin.lock(), &mut std::io::stdout())?
+        } else {
+            Layout::default()
+        };
 
         write_to_user_only_file(
             self.output_file.as_path(),
@@ -285,6 +510,177 @@ impl CliCommand<()> for GenerateLayoutTemplate {
     }
 }
 
+/// Prompts for each value of a layout, starting from the defaults
+///
+/// Values are checked as they're entered with the same checks as [`Layout::validate`], and
+/// re-prompted until they pass.  The root key, users, and total supply are left to be filled in.
+pub(crate) fn prompt_layout<R: BufRead, W: Write>(
+    input: &mut R,
+    output: &mut W,
+) -> CliTypedResult<Layout> {
+    let mut layout = Layout::default();
+    prompt_layout_field(
+        input,
+        output,
+        &mut layout,
+        "Chain id, either a number or a named network e.g. testnet",
+        |layout| layout.chain_id,
+        |layout, value| layout.chain_id = value,
+        None,
+    )?;
+    prompt_layout_field(
+        input,
+        output,
+        &mut layout,
+        "Whether this is a test network",
+        |layout| layout.is_test,
+        |layout, value| layout.is_test = value,
+        None,
+    )?;
+    prompt_layout_field(
+        input,
+        output,
+        &mut layout,
+        "Whether to allow new validators to join after genesis",
+        |layout| layout.allow_new_validators,
+        |layout, value| layout.allow_new_validators = value,
+        None,
+    )?;
+    prompt_layout_field(
+        input,
+        output,
+        &mut layout,
+        "Duration of an epoch in seconds",
+        |layout| layout.epoch_duration_secs,
+        |layout, value| layout.epoch_duration_secs = value,
+        Some(Layout::check_epoch_duration),
+    )?;
+    prompt_layout_field(
+        input,
+        output,
+        &mut layout,
+        "Minimum stake to be in the validator set",
+        |layout| layout.min_stake,
+        |layout, value| layout.min_stake = value,
+        None,
+    )?;
+    prompt_layout_field(
+        input,
+        output,
+        &mut layout,
+        "Maximum stake to be in the validator set",
+        |layout| layout.max_stake,
+        |layout, value| layout.max_stake = value,
+        Some(Layout::check_stake_bounds),
+    )?;
+    prompt_layout_field(
+        input,
+        output,
+        &mut layout,
+        "Minimum number of seconds to lockup staked coins, at least an epoch",
+        |layout| layout.recurring_lockup_duration_secs,
+        |layout, value| layout.recurring_lockup_duration_secs = value,
+        Some(Layout::check_recurring_lockup_duration),
+    )?;
+    prompt_layout_field(
+        input,
+        output,
+        &mut layout,
+        "Minimum number of votes to consider a proposal valid",
+        |layout| layout.min_voting_threshold,
+        |layout, value| layout.min_voting_threshold = value,
+        None,
+    )?;
+    prompt_layout_field(
+        input,
+        output,
+        &mut layout,
+        "Required amount of stake to create proposals",
+        |layout| layout.required_proposer_stake,
+        |layout, value| layout.required_proposer_stake = value,
+        None,
+    )?;
+    prompt_layout_field(
+        input,
+        output,
+        &mut layout,
+        "Voting duration for a proposal in seconds, at most the lockup duration",
+        |layout| layout.voting_duration_secs,
+        |layout, value| layout.voting_duration_secs = value,
+        Some(Layout::check_voting_duration),
+    )?;
+    prompt_layout_field(
+        input,
+        output,
+        &mut layout,
+        "Percentage of stake given out as rewards a year (0-100)",
+        |layout| layout.rewards_apy_percentage,
+        |layout, value| layout.rewards_apy_percentage = value,
+        Some(Layout::check_rewards_apy_percentage),
+    )?;
+    prompt_layout_field(
+        input,
+        output,
+        &mut layout,
+        "Percentage of an epoch's total voting power that can be added in the epoch (1-50)",
+        |layout| layout.voting_power_increase_limit,
+        |layout, value| layout.voting_power_increase_limit = value,
+        Some(Layout::check_voting_power_increase_limit),
+    )?;
+    Ok(layout)
+}
+
+/// Prompts for a single value of a layout until it parses and passes the check
+///
+/// An empty input keeps the current value
+fn prompt_layout_field<T, R: BufRead, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    layout: &mut Layout,
+    description: &str,
+    get: fn(&Layout) -> T,
+    set: fn(&mut Layout, T),
+    check: Option<LayoutCheck>,
+) -> CliTypedResult<()>
+where
+    T: FromStr + Display,
+    T::Err: Display,
+{
+    let write_err = |err| CliError::IO("layout prompt".to_string(), err);
+    loop {
+        writeln!(output, "{} [default: {}] >", description, get(layout)).map_err(write_err)?;
+        let mut line = String::new();
+        if input.read_line(&mut line).map_err(write_err)? == 0 {
+            return Err(CliError::CommandArgumentError(format!(
+                "Input ended before a value was given for: {}",
+                description
+            )));
+        }
+
+        let line = line.trim();
+        let value = if line.is_empty() {
+            get(layout)
+        } else {
+            match T::from_str(line) {
+                Ok(value) => value,
+                Err(err) => {
+                    writeln!(output, "Invalid value '{}': {}", line, err).map_err(write_err)?;
+                    continue;
+                }
+            }
+        };
+
+        let mut candidate = layout.clone();
+        set(&mut candidate, value);
+        if let Some(Err(err)) = check.map(|check| check(&candidate)) {
+            writeln!(output, "{}", err).map_err(write_err)?;
+            continue;
+        }
+        *layout = candidate;
+        return Ok(());
+    }
+}
+
 /// Generate a WriteSet genesis compiled from a script file.
 ///
 /// This will compile a piece of Move script and generate a writeset from that script.
@@ -329,3 +725,206 @@ impl CliCommand<()> for GenerateAdminWriteSet {
         )
     }
 }
+
+/// Generate the admin transactions that reset beneficiaries of employee pools
+///
+/// Takes the employee vesting accounts file used for genesis, and a CSV of
+/// `pool,account,new_beneficiary` rows where `pool` is the index of the pool in the file.  Rows
+/// with an empty `new_beneficiary` remove the account's beneficiary, so distributions go back to
+/// the account itself.  Each transaction is to be sent by the admin of the pool's vesting
+/// contract, which is the pool's owner.
+#[derive(Parser)]
+pub struct GenerateBeneficiaryResetWriteSet {
+    /// Path of the employee vesting accounts file used for genesis
+    #[clap(long, parse(from_os_str))]
+    pub(crate) employee_pools_file: PathBuf,
+    /// Path of the CSV of `pool,account,new_beneficiary` rows
+    #[clap(long, parse(from_os_str))]
+    pub(crate) resets_file: PathBuf,
+    /// Path of the output file, a JSON list of the transactions
+    #[clap(long, parse(from_os_str))]
+    pub(crate) output_file: PathBuf,
+
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+}
+
+/// A transaction for a vesting contract's admin to change one account's beneficiary
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BeneficiaryResetTransaction {
+    /// Row of the resets file this transaction is for
+    pub row: usize,
+    /// Index of the pool in the employee vesting accounts file
+    pub pool_index: usize,
+    /// Admin of the vesting contract, who has to send the transaction
+    pub sender: AccountAddress,
+    pub contract_address: AccountAddress,
+    pub shareholder: AccountAddress,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_beneficiary: Option<AccountAddress>,
+    /// Entry function called e.g. `0x1::vesting::set_beneficiary`
+    pub function: String,
+    /// Hex encoded BCS of the transaction payload
+    pub payload: String,
+}
+
+#[async_trait]
+impl CliCommand<Vec<BeneficiaryResetTransaction>> for GenerateBeneficiaryResetWriteSet {
+    fn command_name(&self) -> &'static str {
+        "GenerateBeneficiaryResetWriteSet"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<BeneficiaryResetTransaction>> {
+        check_if_file_exists(self.output_file.as_path(), self.prompt_options)?;
+        let employee_pools: EmployeePoolMap = from_yaml(&String::from_utf8(read_from_file(
+            self.employee_pools_file.as_path(),
+        )?)?)?;
+        let resets = String::from_utf8(read_from_file(self.resets_file.as_path())?)?;
+        let transactions = beneficiary_reset_transactions(&employee_pools, &resets)?;
+
+        let json = serde_json::to_string_pretty(&transactions)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        write_to_file(
+            self.output_file.as_path(),
+            &self.output_file.display().to_string(),
+            json.as_bytes(),
+        )?;
+        Ok(transactions)
+    }
+}
+
+/// Builds a transaction for each row of the resets CSV, failing with every invalid row
+pub(crate) fn beneficiary_reset_transactions(
+    employee_pools: &EmployeePoolMap,
+    resets: &str,
+) -> CliTypedResult<Vec<BeneficiaryResetTransaction>> {
+    // Genesis creates each pool's vesting contract with the owner as admin, and the admin's nonce
+    // goes up with each contract
+    let mut admin_nonces: HashMap<AccountAddress, u64> = HashMap::new();
+    let contract_addresses: Vec<AccountAddress> = employee_pools
+        .inner
+        .iter()
+        .map(|pool| {
+            let admin = pool.validator.owner_account_address;
+            let nonce = admin_nonces.entry(admin).or_insert(0);
+            let address = create_vesting_contract_address(admin, *nonce, &[]);
+            *nonce += 1;
+            address
+        })
+        .collect();
+
+    let mut transactions = Vec::new();
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+    for (i, line) in resets.lines().enumerate() {
+        let row = i + 1;
+        let line = line.trim();
+        if line.is_empty() || (i == 0 && line.eq_ignore_ascii_case("pool,account,new_beneficiary"))
+        {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let (pool, account, new_beneficiary) = match fields.as_slice() {
+            [pool, account, new_beneficiary] => (*pool, *account, *new_beneficiary),
+            _ => {
+                errors.push(format!(
+                    "row {}: expected `pool,account,new_beneficiary`",
+                    row
+                ));
+                continue;
+            }
+        };
+
+        let pool_index = match usize::from_str(pool) {
+            Ok(index) if index < employee_pools.inner.len() => index,
+            _ => {
+                errors.push(format!(
+                    "row {}: pool '{}' is not one of the {} pools",
+                    row,
+                    pool,
+                    employee_pools.inner.len()
+                ));
+                continue;
+            }
+        };
+        let employee_pool = &employee_pools.inner[pool_index];
+        let shareholder = match AccountAddress::from_str(account) {
+            Ok(address) if employee_pool.accounts.contains(&address) => address,
+            Ok(address) => {
+                errors.push(format!(
+                    "row {}: account {} is not in pool #{}",
+                    row,
+                    address.to_hex_literal(),
+                    pool_index
+                ));
+                continue;
+            }
+            Err(err) => {
+                errors.push(format!(
+                    "row {}: invalid account '{}': {}",
+                    row, account, err
+                ));
+                continue;
+            }
+        };
+        let new_beneficiary = if new_beneficiary.is_empty() {
+            None
+        } else {
+            match AccountAddress::from_str(new_beneficiary) {
+                Ok(address) => Some(address),
+                Err(err) => {
+                    errors.push(format!(
+                        "row {}: invalid new beneficiary '{}': {}",
+                        row, new_beneficiary, err
+                    ));
+                    continue;
+                }
+            }
+        };
+        if !seen.insert((pool_index, shareholder)) {
+            errors.push(format!(
+                "row {}: account {} in pool #{} is already reset by an earlier row",
+                row,
+                shareholder.to_hex_literal(),
+                pool_index
+            ));
+            continue;
+        }
+
+        let contract_address = contract_addresses[pool_index];
+        let (function, payload) = match new_beneficiary {
+            Some(new_beneficiary) => (
+                "set_beneficiary",
+                aptos_stdlib::vesting_set_beneficiary(
+                    contract_address,
+                    shareholder,
+                    new_beneficiary,
+                ),
+            ),
+            None => (
+                "reset_beneficiary",
+                aptos_stdlib::vesting_reset_beneficiary(contract_address, shareholder),
+            ),
+        };
+        transactions.push(BeneficiaryResetTransaction {
+            row,
+            pool_index,
+            sender: employee_pool.validator.owner_account_address,
+            contract_address,
+            shareholder,
+            new_beneficiary,
+            function: format!("0x1::vesting::{}", function),
+            payload: format!("0x{}", hex::encode(bcs::to_bytes(&payload)?)),
+        });
+    }
+
+    if errors.is_empty() {
+        Ok(transactions)
+    } else {
+        Err(CliError::CommandArgumentError(format!(
+            "Invalid rows in resets file: {}",
+            errors.join("; ")
+        )))
+    }
+}
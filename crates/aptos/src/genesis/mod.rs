@@ -3,42 +3,76 @@
 
 pub mod git;
 pub mod keys;
+pub mod summary;
 #[cfg(test)]
 mod tests;
 
 use crate::common::utils::dir_default_to_current;
-use crate::genesis::git::{OPERATOR_FILE, OWNER_FILE};
+use crate::genesis::git::{OPERATOR_FILE, OPERATOR_JSON_FILE, OWNER_FILE, OWNER_JSON_FILE};
 use crate::{
     common::{
-        types::{CliError, CliTypedResult, PromptOptions},
-        utils::{check_if_file_exists, write_to_file},
+        types::{
+            CliError, CliTypedResult, EncodingType, OptionalPoolAddressArgs, PromptOptions, RngArgs,
+        },
+        utils::{
+            check_if_file_exists, create_dir_if_not_exist, read_from_file, start_logger,
+            to_common_result, write_to_file, write_to_user_only_file,
+        },
     },
     genesis::git::{
-        Client, GitOptions, BALANCES_FILE, EMPLOYEE_VESTING_ACCOUNTS_FILE, LAYOUT_FILE,
+        file_hash, from_yaml, to_yaml, Client, GitChanges, GitOptions, BALANCES_DIR, BALANCES_FILE,
+        EMPLOYEE_VESTING_ACCOUNTS_FILE, LAYOUT_FILE,
+    },
+    genesis::summary::{
+        commission_stats, find_unreferenced_balances, genesis_framework_config, genesis_to_json,
+        genesis_total_supply, genesis_validator_set, nakamoto_coefficients, staked_supply,
+        staked_supply_bps, summarize_framework, summarize_genesis_size, summarize_validators,
+        validator_set_hash, BalancesFileSummary, FrameworkPackageSummary, GenesisFrameworkConfig,
+        GenesisManifest, GenesisSeeds, GenesisSizeSummary, GenesisStats, GenesisSummary,
+        NakamotoCoefficients, NodeSeedsConfig, FRAMEWORK_PACKAGE_NAME, MANIFEST_FILE, SEEDS_FILE,
+        SUMMARY_FILE,
     },
     CliCommand, CliResult,
 };
-use aptos_crypto::{bls12381, ed25519::Ed25519PublicKey, x25519, ValidCryptoMaterialStringExt};
+use aptos_crypto::{
+    bls12381, x25519, PrivateKey, ValidCryptoMaterial, ValidCryptoMaterialStringExt,
+};
 use aptos_genesis::builder::GenesisConfiguration;
 use aptos_genesis::config::{
-    AccountBalanceMap, EmployeePoolMap, StringOperatorConfiguration, StringOwnerConfiguration,
+    parse_amount, parse_number, AccountBalanceMap, AccountPublicKey, EmployeePoolMap, HostAndPort,
+    StringOperatorConfiguration, StringOwnerConfiguration,
 };
 use aptos_genesis::{
     config::{Layout, ValidatorConfiguration},
     mainnet::MainnetGenesisInfo,
     GenesisInfo,
 };
-use aptos_logger::info;
+use aptos_logger::{info, warn};
+use aptos_temppath::TempPath;
 use aptos_types::account_address::AccountAddress;
+use aptos_types::chain_id::{ChainId, NamedChain};
+use aptos_types::{transaction::Transaction, waypoint::Waypoint};
 use async_trait::async_trait;
 use clap::Parser;
-use std::collections::BTreeMap;
+use framework::ReleaseBundle;
+use move_deps::move_core_types::account_address::AccountAddressParseError;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
 use std::path::Path;
+use std::time::Instant;
 use std::{path::PathBuf, str::FromStr};
 use vm_genesis::{AccountBalance, EmployeePool};
 
 const WAYPOINT_FILE: &str = "waypoint.txt";
 const GENESIS_FILE: &str = "genesis.blob";
+const GENESIS_JSON_FILE: &str = "genesis.json";
+const ROOT_KEY_FILE: &str = "root-private-key.txt";
+/// Default size of the genesis blob in megabytes over which to warn
+const DEFAULT_GENESIS_SIZE_BUDGET_MB: u64 = 250;
+const BYTES_PER_MB: u64 = 1024 * 1024;
+/// First port used for the validators of a test genesis, each validator gets the next one
+const TEST_VALIDATOR_BASE_PORT: u16 = 6180;
 
 /// Tool for setting up an Aptos chain Genesis transaction
 ///
@@ -47,11 +81,20 @@ const GENESIS_FILE: &str = "genesis.blob";
 #[derive(Parser)]
 pub enum GenesisTool {
     GenerateGenesis(GenerateGenesis),
+    Stats(Stats),
     GenerateKeys(keys::GenerateKeys),
     GenerateLayoutTemplate(keys::GenerateLayoutTemplate),
     GenerateAdminWriteSet(keys::GenerateAdminWriteSet),
+    GenerateBeneficiaryResetWriteSet(keys::GenerateBeneficiaryResetWriteSet),
     SetupGit(git::SetupGit),
     SetValidatorConfiguration(keys::SetValidatorConfiguration),
+    GetValidatorConfig(GetValidatorConfig),
+    GenerateTestGenesis(GenerateTestGenesis),
+    FindEmployeeAccount(FindEmployeeAccount),
+    MergeRepos(git::MergeRepos),
+    ExportValidatorsCsv(ExportValidatorsCsv),
+    ExportNodeSeeds(ExportNodeSeeds),
+    ValidateUser(ValidateUser),
 }
 
 impl GenesisTool {
@@ -61,12 +104,49 @@ impl GenesisTool {
             GenesisTool::GenerateKeys(tool) => tool.execute_serialized().await,
             GenesisTool::GenerateLayoutTemplate(tool) => tool.execute_serialized_success().await,
             GenesisTool::GenerateAdminWriteSet(tool) => tool.execute_serialized_success().await,
+            GenesisTool::GenerateBeneficiaryResetWriteSet(tool) => tool.execute_serialized().await,
             GenesisTool::SetupGit(tool) => tool.execute_serialized_success().await,
             GenesisTool::SetValidatorConfiguration(tool) => tool.execute_serialized_success().await,
+            GenesisTool::GenerateTestGenesis(tool) => tool.execute_serialized().await,
+            GenesisTool::FindEmployeeAccount(tool) => tool.execute_serialized().await,
+            GenesisTool::MergeRepos(tool) => tool.execute_serialized().await,
+            GenesisTool::ExportValidatorsCsv(tool) => tool.execute_serialized().await,
+            GenesisTool::ExportNodeSeeds(tool) => tool.execute_serialized().await,
+            GenesisTool::ValidateUser(tool) => tool.execute_serialized_report().await,
+            GenesisTool::Stats(tool) => execute_formatted("Stats", tool.execute()).await,
+            GenesisTool::GetValidatorConfig(tool) => {
+                if tool.yaml {
+                    let command_name = tool.command_name();
+                    execute_formatted(command_name, async move {
+                        tool.execute().await.and_then(|report| to_yaml(&report))
+                    })
+                    .await
+                } else {
+                    tool.execute_serialized().await
+                }
+            }
         }
     }
 }
 
+/// Runs a command whose output is already formatted, and prints it as is
+///
+/// Errors are wrapped the same way as `execute_serialized`, so the format only changes what's
+/// printed on success.
+async fn execute_formatted(
+    command_name: &str,
+    execute: impl Future<Output = CliTypedResult<String>>,
+) -> CliResult {
+    start_logger();
+    let start_time = Instant::now();
+    match execute.await {
+        Ok(output) => to_common_result(command_name, start_time, Ok(()))
+            .await
+            .map(|_| output),
+        Err(err) => to_common_result::<()>(command_name, start_time, Err(err)).await,
+    }
+}
+
 /// Generate genesis from a git repository
 #[derive(Parser)]
 pub struct GenerateGenesis {
@@ -78,13 +158,128 @@ pub struct GenerateGenesis {
     /// Default is false
     #[clap(long)]
     mainnet: bool,
+    /// Whether to print the hash of the validator set
+    ///
+    /// Coordinators can compare this to confirm they resolved the same validator set.  It is
+    /// also included in the summary file
+    #[clap(long)]
+    print_validator_set_hash: bool,
+    /// Size of the genesis blob in megabytes over which to warn
+    ///
+    /// Large genesis blobs slow down the first sync of nodes, and can break provisioning
+    #[clap(long, default_value_t = DEFAULT_GENESIS_SIZE_BUDGET_MB)]
+    genesis_size_budget_mb: u64,
+    /// Size of the genesis blob in megabytes over which to fail instead of writing it
+    #[clap(long)]
+    max_genesis_size_mb: Option<u64>,
+    /// Whether to also write a readable JSON form of the genesis transaction
+    ///
+    /// The framework is replaced by a hash in `genesis.json`.  It's only for review, the genesis
+    /// blob is what nodes use
+    #[clap(long)]
+    emit_json_genesis: bool,
+    /// Encoding of the waypoint file [plain, hex, base64]
+    ///
+    /// Hex and base64 encode the plain `version:hash` form of the waypoint
+    #[clap(long, default_value_t = WaypointEncoding::Plain)]
+    waypoint_encoding: WaypointEncoding,
+    /// File to write a JSON manifest of the inputs to
+    ///
+    /// The manifest has the SHA-256 hash of every file read from the repository, and the commit
+    /// they were read at.  Coordinators can compare manifests to prove they built genesis from
+    /// identical inputs
+    #[clap(long, parse(from_os_str))]
+    manifest: Option<PathBuf>,
+    /// Whether to decode the written genesis blob and check it against the inputs
+    ///
+    /// Checks the number of validators joining during genesis, and for mainnet the total supply.
+    /// On a mismatch, the genesis blob and waypoint are removed
+    #[clap(long)]
+    verify_output: bool,
+    /// Whether to also check the framework config written to 0x1 against the layout
+    ///
+    /// Decodes the chain id, staking config, and governance config from the genesis blob, and
+    /// reports every value that differs from the layout.  Implies `--verify-output`
+    #[clap(long)]
+    deep_verify: bool,
 
+    #[clap(flatten)]
+    genesis_options: GenesisOptions,
     #[clap(flatten)]
     prompt_options: PromptOptions,
     #[clap(flatten)]
     git_options: GitOptions,
 }
 
+/// Options for fetching and validating the genesis inputs in the git repository
+#[derive(Clone, Default, Parser)]
+pub struct GenesisOptions {
+    /// Override for the chain id in the layout file
+    ///
+    /// Can be a number or a named network e.g. `mainnet`, `testnet`, or `premainnet`
+    #[clap(long)]
+    pub(crate) chain_id: Option<ChainId>,
+    /// Whether to allow a chain id reserved for a named network that the layout doesn't match
+    ///
+    /// e.g. Using the mainnet chain id for a test network
+    #[clap(long)]
+    pub(crate) allow_reserved_chain_id: bool,
+    /// Whether to fail if the balances file has accounts that aren't referenced by any validator
+    /// or employee pool
+    ///
+    /// Only used for mainnet genesis, otherwise these accounts are reported as a warning
+    #[clap(long)]
+    pub(crate) fail_on_unreferenced_balances: bool,
+    /// Expected version of the AptosFramework package in the git repository e.g. `1.0.0`
+    ///
+    /// Guards against building genesis with a stale framework
+    #[clap(long)]
+    pub(crate) expected_framework_version: Option<String>,
+    /// Whether to fail if a validator's full node host is another validator's validator host
+    ///
+    /// Otherwise these collisions are reported as a warning
+    #[clap(long)]
+    pub(crate) fail_on_host_collisions: bool,
+    /// Whether to sort the initial balances by account address
+    ///
+    /// Only used for mainnet genesis.  Accounts are created in the order they're listed, and that
+    /// order shows up in the genesis transaction, so the waypoint depends on it.  Sorting gives
+    /// the same waypoint for balances files that list the same accounts in different orders.
+    #[clap(long)]
+    pub(crate) sort_accounts: bool,
+    /// File to write each account's balance to if the balances don't add up to the total supply
+    ///
+    /// Only used for mainnet genesis.  The file is a balances CSV sorted by account address, so
+    /// it can be diffed against the expected balances
+    #[clap(long, parse(from_os_str))]
+    pub(crate) debug_supply: Option<PathBuf>,
+    /// Whether to hide progress notes e.g. the size of genesis
+    ///
+    /// Warnings and errors are still shown, and the result is still printed
+    #[clap(long)]
+    pub(crate) quiet: bool,
+    /// Whether to resolve validator and full node hosts that are DNS names to IP addresses
+    ///
+    /// The resolved addresses are what's stored in genesis.  Hosts are resolved with the system
+    /// resolver, so only A and AAAA records are used.
+    #[clap(long)]
+    pub(crate) resolve_hosts: bool,
+    /// Whether to fail if an account's public key doesn't derive its address
+    ///
+    /// Otherwise these mismatches are reported as a warning
+    #[clap(long)]
+    pub(crate) strict: bool,
+}
+
+impl GenesisOptions {
+    /// Prints a progress note to stderr, unless `--quiet` was given
+    fn note(&self, message: String) {
+        if !self.quiet {
+            eprintln!("{}", message);
+        }
+    }
+}
+
 #[async_trait]
 impl CliCommand<Vec<PathBuf>> for GenerateGenesis {
     fn command_name(&self) -> &'static str {
@@ -95,157 +290,1866 @@ impl CliCommand<Vec<PathBuf>> for GenerateGenesis {
         let output_dir = dir_default_to_current(self.output_dir.clone())?;
         let genesis_file = output_dir.join(GENESIS_FILE);
         let waypoint_file = output_dir.join(WAYPOINT_FILE);
+        let summary_file = output_dir.join(SUMMARY_FILE);
+        let seeds_file = output_dir.join(SEEDS_FILE);
         check_if_file_exists(genesis_file.as_path(), self.prompt_options)?;
         check_if_file_exists(waypoint_file.as_path(), self.prompt_options)?;
+        check_if_file_exists(summary_file.as_path(), self.prompt_options)?;
+        check_if_file_exists(seeds_file.as_path(), self.prompt_options)?;
+        let genesis_json_file = output_dir.join(GENESIS_JSON_FILE);
+        if self.emit_json_genesis {
+            check_if_file_exists(genesis_json_file.as_path(), self.prompt_options)?;
+        }
+        if let Some(ref manifest_file) = self.manifest {
+            check_if_file_exists(manifest_file.as_path(), self.prompt_options)?;
+        }
+
+        // Only look up the commit if it's needed, as it's another request to Github
+        let source = self.git_options.source();
+        let client = self.git_options.get_client()?;
+        let commit = if self.manifest.is_some() {
+            client.head_commit()?
+        } else {
+            None
+        };
 
         // Generate genesis and waypoint files
-        let (genesis_bytes, waypoint) = if self.mainnet {
-            let mut mainnet_genesis = fetch_mainnet_genesis_info(self.git_options)?;
-            let genesis_bytes = bcs::to_bytes(mainnet_genesis.clone().get_genesis())
-                .map_err(|e| CliError::BCS(GENESIS_FILE, e))?;
-            (genesis_bytes, mainnet_genesis.generate_waypoint()?)
+        let (genesis, waypoint, mut summary) = if self.mainnet {
+            let (mut mainnet_genesis, summary) =
+                fetch_mainnet_genesis_info_from(&client, &self.genesis_options)?;
+            let genesis = mainnet_genesis.clone().get_genesis().clone();
+            (genesis, mainnet_genesis.generate_waypoint()?, summary)
         } else {
-            let mut test_genesis = fetch_genesis_info(self.git_options)?;
-            let genesis_bytes = bcs::to_bytes(test_genesis.clone().get_genesis())
-                .map_err(|e| CliError::BCS(GENESIS_FILE, e))?;
-            (genesis_bytes, test_genesis.generate_waypoint()?)
+            let (mut test_genesis, summary) =
+                fetch_genesis_info_from(&client, &self.genesis_options)?;
+            let genesis = test_genesis.clone().get_genesis().clone();
+            (genesis, test_genesis.generate_waypoint()?, summary)
         };
-        write_to_file(genesis_file.as_path(), GENESIS_FILE, &genesis_bytes)?;
-        write_to_file(
+
+        // Files are read from the branch rather than the commit, so if the branch moved while
+        // reading, the hashes can't be attributed to either commit
+        let manifest = if self.manifest.is_some() {
+            let head_commit = client.head_commit()?;
+            if head_commit != commit {
+                return Err(CliError::UnexpectedError(format!(
+                    "Branch moved from commit {} to {} while reading the genesis inputs, try again",
+                    commit.unwrap_or_default(),
+                    head_commit.unwrap_or_default()
+                )));
+            }
+            Some(GenesisManifest::new(source, commit, client.reads()))
+        } else {
+            None
+        };
+        let genesis_bytes = bcs::to_bytes(&genesis).map_err(|e| CliError::BCS(GENESIS_FILE, e))?;
+        summary.genesis_size = Some(check_genesis_size(
+            &genesis,
+            genesis_bytes.len() as u64,
+            self.genesis_size_budget_mb,
+            self.max_genesis_size_mb,
+            &self.genesis_options,
+        )?);
+        if self.print_validator_set_hash {
+            if let Some(hash) = summary.validator_set_hash {
+                eprintln!("Validator set hash: {}", hash);
+            }
+        }
+        write_genesis_outputs(
+            genesis_file.as_path(),
             waypoint_file.as_path(),
-            WAYPOINT_FILE,
-            waypoint.to_string().as_bytes(),
+            &genesis_bytes,
+            waypoint,
+            self.waypoint_encoding,
+        )?;
+        if self.verify_output || self.deep_verify {
+            let result =
+                verify_genesis_contents(genesis_file.as_path(), &summary, &self.genesis_options)
+                    .and_then(|_| {
+                        if self.deep_verify {
+                            let layout = load_and_validate_layout(&client, self.mainnet)?;
+                            verify_framework_config(
+                                genesis_file.as_path(),
+                                &layout,
+                                &summary,
+                                self.mainnet,
+                                &self.genesis_options,
+                            )
+                        } else {
+                            Ok(())
+                        }
+                    });
+            if let Err(err) = result {
+                let _ = std::fs::remove_file(genesis_file.as_path());
+                let _ = std::fs::remove_file(waypoint_file.as_path());
+                return Err(err);
+            }
+        }
+        write_to_file(
+            summary_file.as_path(),
+            SUMMARY_FILE,
+            to_yaml(&summary)?.as_bytes(),
+        )?;
+        write_to_file(
+            seeds_file.as_path(),
+            SEEDS_FILE,
+            to_yaml(&GenesisSeeds::from_summaries(&summary.validators))?.as_bytes(),
         )?;
-        Ok(vec![genesis_file, waypoint_file])
+        let mut files = vec![genesis_file, waypoint_file, summary_file, seeds_file];
+
+        if self.emit_json_genesis {
+            // Convert what was written, rather than the in memory transaction
+            let written_genesis: Transaction =
+                bcs::from_bytes(&genesis_bytes).map_err(|e| CliError::BCS(GENESIS_FILE, e))?;
+            let genesis_json = serde_json::to_string_pretty(&genesis_to_json(&written_genesis)?)
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            write_to_file(
+                genesis_json_file.as_path(),
+                GENESIS_JSON_FILE,
+                genesis_json.as_bytes(),
+            )?;
+            files.push(genesis_json_file);
+        }
+        if let (Some(manifest_file), Some(manifest)) = (self.manifest, manifest) {
+            let manifest = serde_json::to_string_pretty(&manifest)
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            write_to_file(manifest_file.as_path(), MANIFEST_FILE, manifest.as_bytes())?;
+            files.push(manifest_file);
+        }
+        Ok(files)
     }
 }
 
-/// Retrieves all information for mainnet genesis from the Git repository
-pub fn fetch_mainnet_genesis_info(git_options: GitOptions) -> CliTypedResult<MainnetGenesisInfo> {
-    let client = git_options.get_client()?;
-    let layout: Layout = client.get(Path::new(LAYOUT_FILE))?;
+/// Generate genesis for a local test network with freshly generated validator keys
+///
+/// Each validator's keys are written to its own directory in the output directory, so that nodes
+/// can be configured with them.  The validators, layout, and balances are put into a temporary
+/// local repository, and genesis is generated from it the same way as `GenerateGenesis`.
+#[derive(Parser)]
+pub struct GenerateTestGenesis {
+    /// Output directory for the genesis file, waypoint, and keys
+    #[clap(long, parse(from_os_str))]
+    output_dir: Option<PathBuf>,
+    /// Number of validators to generate
+    #[clap(long, default_value_t = 1)]
+    num_validators: u16,
 
-    let total_supply = layout.total_supply.ok_or_else(|| {
-        CliError::UnexpectedError("Layout file does not have `total_supply`".to_string())
-    })?;
+    #[clap(flatten)]
+    prompt_options: PromptOptions,
+    #[clap(flatten)]
+    rng_args: RngArgs,
+}
 
-    let account_balance_map: AccountBalanceMap = client.get(Path::new(BALANCES_FILE))?;
-    let accounts: Vec<AccountBalance> = account_balance_map.try_into()?;
+#[async_trait]
+impl CliCommand<Vec<PathBuf>> for GenerateTestGenesis {
+    fn command_name(&self) -> &'static str {
+        "GenerateTestGenesis"
+    }
 
-    // Check that the supply matches the total
-    let total_balance_supply: u64 = accounts.iter().map(|inner| inner.balance).sum();
-    if total_supply != total_balance_supply {
+    async fn execute(self) -> CliTypedResult<Vec<PathBuf>> {
+        if self.num_validators == 0 {
+            return Err(CliError::CommandArgumentError(
+                "--num-validators must be at least 1".to_string(),
+            ));
+        }
+        // Each validator gets the next port up from the base port
+        let max_validators = u16::MAX - TEST_VALIDATOR_BASE_PORT + 1;
+        if self.num_validators > max_validators {
+            return Err(CliError::CommandArgumentError(format!(
+                "--num-validators must be at most {}, so every validator has a port",
+                max_validators
+            )));
+        }
+        let output_dir = dir_default_to_current(self.output_dir.clone())?;
+        let root_key_file = output_dir.join(ROOT_KEY_FILE);
+        check_if_file_exists(root_key_file.as_path(), self.prompt_options)?;
+        create_dir_if_not_exist(output_dir.as_path())?;
+
+        let repository = TempPath::new();
+        repository
+            .create_as_dir()
+            .map_err(|err| CliError::IO(repository.path().display().to_string(), err))?;
+        let git_options = GitOptions {
+            local_repository_dir: Some(PathBuf::from(repository.path())),
+            ..Default::default()
+        };
+        let client = git_options.clone().get_client()?;
+
+        let mut key_generator = self.rng_args.key_generator()?;
+        let root_key = key_generator.generate_ed25519_private_key();
+        let users: Vec<String> = (0..self.num_validators)
+            .map(|i| format!("validator-{}", i))
+            .collect();
+        let layout = Layout {
+            root_key: Some(root_key.public_key()),
+            users: users.clone(),
+            ..Default::default()
+        };
+        // The repository files are committed together once the balances are known
+        let mut changes = GitChanges::default();
+        changes.put(Path::new(LAYOUT_FILE), &layout)?;
+        changes.put_bytes(
+            Path::new(git::FRAMEWORK_NAME),
+            bcs::to_bytes(cached_packages::head_release_bundle())?,
+        );
+
+        let mut files = Vec::new();
+        let mut account_balances = Vec::new();
+        for (i, user) in users.iter().enumerate() {
+            // Seed each validator's keys from the generator, so a given seed reproduces all keys
+            let mut seed = [0u8; 32];
+            seed.copy_from_slice(&key_generator.generate_ed25519_private_key().to_bytes());
+            let keys_dir = output_dir.join(user);
+            files.extend(
+                keys::GenerateKeys {
+                    output_dir: Some(keys_dir.clone()),
+                    split_role_files: false,
+                    operator_address: None,
+                    pool_address_args: OptionalPoolAddressArgs { pool_address: None },
+                    prompt_options: self.prompt_options,
+                    rng_args: RngArgs::from_seed(seed),
+                }
+                .execute()
+                .await?,
+            );
+
+            let public_keys_file = keys_dir.join(keys::PUBLIC_KEYS_FILE);
+            let owner = keys::read_public_identity_file(public_keys_file.as_path())?;
+            keys::SetValidatorConfiguration {
+                username: user.clone(),
+                validator_host: HostAndPort::from_str(&format!(
+                    "127.0.0.1:{}",
+                    TEST_VALIDATOR_BASE_PORT + i as u16
+                ))?,
+                full_node_host: None,
+                private_validator_address: false,
+                stake_amount: layout.min_stake,
+                commission_percentage: 0,
+                join_during_genesis: true,
+                owner_public_identity_file: Some(public_keys_file),
+                operator_public_identity_file: None,
+                voter_public_identity_file: None,
+                git_options: git_options.clone(),
+            }
+            .execute()
+            .await?;
+            account_balances.push(BTreeMap::from([(owner.account_address, layout.min_stake)]));
+        }
+        changes.put(
+            Path::new(BALANCES_FILE),
+            &AccountBalanceMap { account_balances },
+        )?;
+        client.commit(changes, "Setup test genesis repository")?;
+
+        write_to_user_only_file(
+            root_key_file.as_path(),
+            ROOT_KEY_FILE,
+            &EncodingType::Hex.encode_key("root key", &root_key)?,
+        )?;
+        files.push(root_key_file);
+
+        files.extend(
+            GenerateGenesis {
+                output_dir: Some(output_dir),
+                mainnet: false,
+                print_validator_set_hash: false,
+                genesis_size_budget_mb: DEFAULT_GENESIS_SIZE_BUDGET_MB,
+                max_genesis_size_mb: None,
+                emit_json_genesis: false,
+                waypoint_encoding: WaypointEncoding::Plain,
+                manifest: None,
+                verify_output: false,
+                deep_verify: false,
+                genesis_options: GenesisOptions::default(),
+                prompt_options: self.prompt_options,
+                git_options,
+            }
+            .execute()
+            .await?,
+        );
+        Ok(files)
+    }
+}
+
+/// Reports the size of the genesis blob, warning if it's over budget and failing if it's over the
+/// maximum
+fn check_genesis_size(
+    genesis: &Transaction,
+    total_bytes: u64,
+    budget_mb: u64,
+    max_mb: Option<u64>,
+    genesis_options: &GenesisOptions,
+) -> CliTypedResult<GenesisSizeSummary> {
+    let budget_bytes = mb_to_bytes("--genesis-size-budget-mb", budget_mb)?;
+    let max_bytes = max_mb
+        .map(|max_mb| mb_to_bytes("--max-genesis-size-mb", max_mb))
+        .transpose()?;
+    let size = summarize_genesis_size(genesis, total_bytes, budget_bytes)?;
+    genesis_options.note(format!(
+        "Genesis is {} bytes: framework {} bytes ({}.{:02}%), validator set {} bytes, other resources {} bytes",
+        size.total_bytes,
+        size.framework_bytes,
+        size.framework_bps / 100,
+        size.framework_bps % 100,
+        size.validator_set_bytes,
+        size.resources_bytes
+    ));
+
+    if let (Some(max_mb), Some(max_bytes)) = (max_mb, max_bytes) {
+        if size.total_bytes > max_bytes {
+            return Err(CliError::UnexpectedError(format!(
+                "Genesis is {} bytes, over the maximum of {} MB",
+                size.total_bytes, max_mb
+            )));
+        }
+    }
+    if size.over_budget {
+        warn!(
+            "Genesis is {} bytes, over the budget of {} MB.  This may slow down the first sync of nodes",
+            size.total_bytes, budget_mb
+        );
+    }
+    Ok(size)
+}
+
+/// Converts a size argument from megabytes to bytes
+fn mb_to_bytes(arg: &str, mb: u64) -> CliTypedResult<u64> {
+    mb.checked_mul(BYTES_PER_MB)
+        .ok_or_else(|| CliError::CommandArgumentError(format!("{} {} MB is too large", arg, mb)))
+}
+
+/// Encoding of the waypoint file
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WaypointEncoding {
+    /// The waypoint as is e.g. `0:abcd...`
+    Plain,
+    /// Hex of the plain waypoint
+    Hex,
+    /// Base 64 of the plain waypoint
+    Base64,
+}
+
+impl WaypointEncoding {
+    pub fn encode(&self, waypoint: &Waypoint) -> Vec<u8> {
+        let waypoint = waypoint.to_string();
+        match self {
+            WaypointEncoding::Plain => waypoint.into_bytes(),
+            WaypointEncoding::Hex => hex::encode(waypoint).into_bytes(),
+            WaypointEncoding::Base64 => base64::encode(waypoint).into_bytes(),
+        }
+    }
+
+    pub fn decode(&self, bytes: &[u8]) -> CliTypedResult<Waypoint> {
+        let encoded = String::from_utf8(bytes.to_vec())?;
+        let encoded = encoded.trim();
+        let waypoint = match self {
+            WaypointEncoding::Plain => encoded.to_string(),
+            WaypointEncoding::Hex => String::from_utf8(
+                hex::decode(encoded).map_err(|err| CliError::UnexpectedError(err.to_string()))?,
+            )?,
+            WaypointEncoding::Base64 => String::from_utf8(base64::decode(encoded)?)?,
+        };
+        Waypoint::from_str(&waypoint).map_err(|err| CliError::UnexpectedError(err.to_string()))
+    }
+}
+
+impl std::fmt::Display for WaypointEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            WaypointEncoding::Plain => "plain",
+            WaypointEncoding::Hex => "hex",
+            WaypointEncoding::Base64 => "base64",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+impl FromStr for WaypointEncoding {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "plain" => Ok(WaypointEncoding::Plain),
+            "hex" => Ok(WaypointEncoding::Hex),
+            "base64" => Ok(WaypointEncoding::Base64),
+            _ => Err("Invalid waypoint encoding, must be one of [plain, hex, base64]"),
+        }
+    }
+}
+
+/// Writes the genesis blob and waypoint, then checks that the waypoint matches the blob on disk
+///
+/// On a mismatch, both files are removed so that a bad pair can't be distributed
+fn write_genesis_outputs(
+    genesis_file: &Path,
+    waypoint_file: &Path,
+    genesis_bytes: &[u8],
+    waypoint: Waypoint,
+    waypoint_encoding: WaypointEncoding,
+) -> CliTypedResult<()> {
+    write_to_file(genesis_file, GENESIS_FILE, genesis_bytes)?;
+    write_to_file(
+        waypoint_file,
+        WAYPOINT_FILE,
+        &waypoint_encoding.encode(&waypoint),
+    )?;
+
+    if let Err(err) = verify_genesis_outputs(genesis_file, waypoint_file, waypoint_encoding) {
+        let _ = std::fs::remove_file(genesis_file);
+        let _ = std::fs::remove_file(waypoint_file);
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// Recomputes the waypoint from the genesis blob on disk, and compares it to the waypoint file
+fn verify_genesis_outputs(
+    genesis_file: &Path,
+    waypoint_file: &Path,
+    waypoint_encoding: WaypointEncoding,
+) -> CliTypedResult<()> {
+    let genesis: Transaction = bcs::from_bytes(&read_from_file(genesis_file)?)
+        .map_err(|e| CliError::BCS(GENESIS_FILE, e))?;
+    let computed_waypoint = aptos_genesis::generate_waypoint(&genesis)?;
+
+    let written_waypoint = waypoint_encoding
+        .decode(&read_from_file(waypoint_file)?)
+        .map_err(|err| {
+            CliError::UnexpectedError(format!(
+                "Failed to parse {} waypoint file {}: {}",
+                waypoint_encoding,
+                waypoint_file.display(),
+                err
+            ))
+        })?;
+
+    if computed_waypoint != written_waypoint {
         return Err(CliError::UnexpectedError(format!(
-            "Total supply seen {} doesn't match expected total supply {}",
-            total_balance_supply, total_supply
+            "Waypoint {} in {} does not match waypoint {} computed from {}, removing both",
+            written_waypoint,
+            waypoint_file.display(),
+            computed_waypoint,
+            genesis_file.display()
         )));
     }
+    Ok(())
+}
 
-    // Keep track of accounts for later lookup of balances
-    let initialized_accounts: BTreeMap<AccountAddress, u64> = accounts
+/// Decodes the genesis blob on disk, and checks that it has the validators and total supply that
+/// were loaded from the repository
+fn verify_genesis_contents(
+    genesis_file: &Path,
+    summary: &GenesisSummary,
+    genesis_options: &GenesisOptions,
+) -> CliTypedResult<()> {
+    let genesis: Transaction = bcs::from_bytes(&read_from_file(genesis_file)?)
+        .map_err(|e| CliError::BCS(GENESIS_FILE, e))?;
+
+    let expected_validators = summary
+        .validators
         .iter()
-        .map(|inner| (inner.account_address, inner.balance))
-        .collect();
+        .filter(|validator| validator.join_during_genesis)
+        .count();
+    let validator_set = genesis_validator_set(&genesis)?;
+    let num_validators = validator_set.active_validators.len() + validator_set.pending_active.len();
+    if num_validators != expected_validators {
+        return Err(CliError::UnexpectedError(format!(
+            "{} has {} validators, but {} validators are joining during genesis, removing it",
+            genesis_file.display(),
+            num_validators,
+            expected_validators
+        )));
+    }
 
-    let employee_vesting_accounts: EmployeePoolMap =
-        client.get(Path::new(EMPLOYEE_VESTING_ACCOUNTS_FILE))?;
+    // Only mainnet genesis has a total supply to check against
+    if let Some(expected_supply) = summary.total_supply {
+        let total_supply = genesis_total_supply(&genesis)?;
+        if total_supply != Some(expected_supply as u128) {
+            return Err(CliError::UnexpectedError(format!(
+                "{} has a total supply of {}, but the layout has a total supply of {}, removing it",
+                genesis_file.display(),
+                total_supply
+                    .map(|supply| supply.to_string())
+                    .unwrap_or_else(|| "untracked".to_string()),
+                expected_supply
+            )));
+        }
+    }
 
-    let employee_validators: Vec<_> = employee_vesting_accounts
-        .inner
-        .iter()
-        .map(|inner| inner.validator.clone())
-        .collect();
-    let employee_vesting_accounts: Vec<EmployeePool> = employee_vesting_accounts.try_into()?;
-    let validators = get_validator_configs(&client, &layout, true).map_err(parse_error)?;
+    genesis_options.note(format!(
+        "Verified {}: {} validators{}",
+        genesis_file.display(),
+        num_validators,
+        summary
+            .total_supply
+            .map(|supply| format!(", total supply {}", supply))
+            .unwrap_or_default()
+    ));
+    Ok(())
+}
 
-    // Check accounts for employee accounts
-    for (i, pool) in employee_vesting_accounts.iter().enumerate() {
-        let mut total_stake_pool_amount = 0;
-        for (j, account) in pool.accounts.iter().enumerate() {
-            if !initialized_accounts.contains_key(account) {
-                return Err(CliError::UnexpectedError(format!(
-                    "Account #{} '{}' in pool #{} is is not in the initialized balances",
-                    j, account, i
-                )));
+/// Checks that the framework config genesis wrote to 0x1 matches the layout
+///
+/// Every mismatched value is reported, rather than only the first
+fn verify_framework_config(
+    genesis_file: &Path,
+    layout: &Layout,
+    summary: &GenesisSummary,
+    mainnet: bool,
+    genesis_options: &GenesisOptions,
+) -> CliTypedResult<()> {
+    let genesis: Transaction = bcs::from_bytes(&read_from_file(genesis_file)?)
+        .map_err(|e| CliError::BCS(GENESIS_FILE, e))?;
+    let actual = genesis_framework_config(&genesis)?;
+    let expected = GenesisFrameworkConfig {
+        // The chain id can be overridden on the command line, so use the one genesis was built with
+        chain_id: summary.chain_id.unwrap_or_else(|| layout.chain_id.id()),
+        min_stake: layout.min_stake,
+        max_stake: layout.max_stake,
+        recurring_lockup_duration_secs: layout.recurring_lockup_duration_secs,
+        // Mainnet always allows new validators to join after genesis
+        allow_new_validators: mainnet || layout.allow_new_validators,
+        voting_power_increase_limit: layout.voting_power_increase_limit,
+        min_voting_threshold: layout.min_voting_threshold,
+        required_proposer_stake: layout.required_proposer_stake,
+        voting_duration_secs: layout.voting_duration_secs,
+    };
+
+    let mismatches = framework_config_mismatches(&actual, &expected)?;
+    if !mismatches.is_empty() {
+        return Err(CliError::UnexpectedError(format!(
+            "{} doesn't match the layout, removing it: {}",
+            genesis_file.display(),
+            mismatches.join("; ")
+        )));
+    }
+
+    genesis_options.note(format!(
+        "Verified the framework config in {} against {}",
+        genesis_file.display(),
+        LAYOUT_FILE
+    ));
+    Ok(())
+}
+
+/// Lists each field of the framework config that differs, as `field is actual, expected value`
+fn framework_config_mismatches(
+    actual: &GenesisFrameworkConfig,
+    expected: &GenesisFrameworkConfig,
+) -> CliTypedResult<Vec<String>> {
+    let to_map = |config: &GenesisFrameworkConfig| match serde_json::to_value(config) {
+        Ok(serde_json::Value::Object(map)) => Ok(map),
+        Ok(_) => Err(CliError::UnexpectedError(
+            "Framework config isn't a JSON object".to_string(),
+        )),
+        Err(err) => Err(CliError::UnexpectedError(err.to_string())),
+    };
+    let actual = to_map(actual)?;
+    let expected = to_map(expected)?;
+    Ok(expected
+        .iter()
+        .filter_map(|(field, expected_value)| {
+            let actual_value = actual.get(field)?;
+            if actual_value == expected_value {
+                None
+            } else {
+                Some(format!(
+                    "{} is {}, expected {}",
+                    field, actual_value, expected_value
+                ))
             }
-            total_stake_pool_amount += initialized_accounts.get(account).unwrap();
-        }
+        })
+        .collect())
+}
 
-        if total_stake_pool_amount != pool.validator.validator.stake_amount {
-            return Err(CliError::UnexpectedError(format!(
-                "Stake amount {} in pool #{} does not match combined of accounts {}",
-                pool.validator.validator.stake_amount, i, total_stake_pool_amount
+/// Fetch and display one user's validator configuration from the git repository
+///
+/// The owner and operator files are parsed the same way as in `GenerateGenesis`, so any errors
+/// are the ones genesis generation would hit.
+#[derive(Parser)]
+pub struct GetValidatorConfig {
+    /// Name of the user in the layout file
+    #[clap(long)]
+    pub(crate) username: String,
+    /// Whether the configuration is for mainnet genesis
+    ///
+    /// Mainnet configurations are also validated against the balances file
+    #[clap(long)]
+    pub(crate) mainnet: bool,
+    /// Print the configuration as YAML rather than JSON
+    #[clap(long)]
+    pub(crate) yaml: bool,
+    /// Whether to fail if an account's public key doesn't derive its address
+    ///
+    /// Otherwise these mismatches are reported as a warning
+    #[clap(long)]
+    pub(crate) strict: bool,
+
+    #[clap(flatten)]
+    pub(crate) git_options: GitOptions,
+}
+
+/// A user's validator configuration, along with facts derived from the rest of the repository
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ValidatorConfigReport {
+    pub username: String,
+    pub owner_account_address: String,
+    pub owner_account_public_key: String,
+    pub operator_account_address: String,
+    pub operator_account_public_key: String,
+    pub voter_account_address: String,
+    pub voter_account_public_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consensus_public_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validator_network_public_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validator_host: Option<HostAndPort>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_node_network_public_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_node_host: Option<HostAndPort>,
+    pub stake_amount: u64,
+    pub commission_percentage: u64,
+    pub join_during_genesis: bool,
+    pub publish_validator_address: bool,
+    /// Whether the configuration passes the checks `GenerateGenesis` runs on it
+    pub passes_validation: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validation_error: Option<String>,
+    /// Stake amount as basis points of the total balance in the balances file, mainnet only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stake_share_bps: Option<u64>,
+}
+
+#[async_trait]
+impl CliCommand<ValidatorConfigReport> for GetValidatorConfig {
+    fn command_name(&self) -> &'static str {
+        "GetValidatorConfig"
+    }
+
+    async fn execute(self) -> CliTypedResult<ValidatorConfigReport> {
+        let client = self.git_options.get_client()?;
+        let layout: Layout = client.get(Path::new(LAYOUT_FILE))?;
+        if !layout.users.contains(&self.username) {
+            return Err(CliError::CommandArgumentError(format!(
+                "User {} is not in the layout file",
+                self.username
             )));
         }
+        let validator = get_config(&client, &self.username, self.mainnet, self.strict)?;
+
+        // Only mainnet genesis checks validators against the balances
+        let (validation, stake_share_bps) = if self.mainnet {
+            let (accounts, _) = load_balances(&client)?;
+            let initialized_accounts: BTreeMap<AccountAddress, u64> = accounts
+                .iter()
+                .map(|inner| (inner.account_address, inner.balance))
+                .collect();
+            let total_balance: u128 = accounts.iter().map(|inner| inner.balance as u128).sum();
+            let stake_share_bps = if total_balance == 0 {
+                None
+            } else {
+                Some((validator.stake_amount as u128 * 10_000 / total_balance) as u64)
+            };
+            (
+                validate_validators(
+                    &layout,
+                    std::slice::from_ref(&validator),
+                    &initialized_accounts,
+                    false,
+                ),
+                stake_share_bps,
+            )
+        } else {
+            (Ok(()), None)
+        };
+
+        Ok(ValidatorConfigReport {
+            username: self.username,
+            owner_account_address: validator.owner_account_address.to_hex_literal(),
+            owner_account_public_key: abbreviate_key(&validator.owner_account_public_key),
+            operator_account_address: validator.operator_account_address.to_hex_literal(),
+            operator_account_public_key: abbreviate_key(&validator.operator_account_public_key),
+            voter_account_address: validator.voter_account_address.to_hex_literal(),
+            voter_account_public_key: abbreviate_key(&validator.voter_account_public_key),
+            consensus_public_key: validator.consensus_public_key.as_ref().map(abbreviate_key),
+            validator_network_public_key: validator
+                .validator_network_public_key
+                .as_ref()
+                .map(abbreviate_key),
+            validator_host: validator.validator_host,
+            full_node_network_public_key: validator
+                .full_node_network_public_key
+                .as_ref()
+                .map(abbreviate_key),
+            full_node_host: validator.full_node_host,
+            stake_amount: validator.stake_amount,
+            commission_percentage: validator.commission_percentage,
+            join_during_genesis: validator.join_during_genesis,
+            publish_validator_address: validator.publish_validator_address,
+            passes_validation: validation.is_ok(),
+            validation_error: validation.err().map(|err| err.to_string()),
+            stake_share_bps,
+        })
+    }
+}
+
+/// Check one user's validator configuration before genesis is generated
+///
+/// Only the layout and the user's own files are read, along with the balances for mainnet, so
+/// operators can check their configuration without the rest of the repository being ready.  The
+/// command fails if any check does, after printing the result of every check.
+#[derive(Parser)]
+pub struct ValidateUser {
+    /// Name of the user in the layout file
+    #[clap(long)]
+    pub(crate) user: String,
+    /// Whether the configuration is for mainnet genesis
+    ///
+    /// Mainnet configurations are also validated against the layout and balances file
+    #[clap(long)]
+    pub(crate) mainnet: bool,
+    /// Whether to fail if an account's public key doesn't derive its address
+    ///
+    /// Otherwise these mismatches are reported as a warning
+    #[clap(long)]
+    pub(crate) strict: bool,
+
+    #[clap(flatten)]
+    pub(crate) git_options: GitOptions,
+}
+
+/// Results of the checks on one user's validator configuration
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UserValidationReport {
+    pub user: String,
+    /// Whether every check passed
+    pub passes: bool,
+    pub checks: Vec<UserCheck>,
+}
+
+/// One check on a user's validator configuration
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UserCheck {
+    /// One of `parse`, `proof_of_possession`, `hosts_and_keys`, or `genesis`
+    pub check: String,
+    pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl UserCheck {
+    fn new(check: &str, result: CliTypedResult<()>) -> Self {
+        UserCheck {
+            check: check.to_string(),
+            passed: result.is_ok(),
+            error: result.err().map(|err| err.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl CliCommand<UserValidationReport> for ValidateUser {
+    fn command_name(&self) -> &'static str {
+        "ValidateUser"
+    }
+
+    async fn execute(self) -> CliTypedResult<UserValidationReport> {
+        let client = self.git_options.get_client()?;
+        let layout: Layout = client.get(Path::new(LAYOUT_FILE))?;
+        if !layout.users.contains(&self.user) {
+            return Err(CliError::CommandArgumentError(format!(
+                "User {} is not in the layout file",
+                self.user
+            )));
+        }
+
+        // Nothing else can be checked if the files don't parse
+        let mut checks = Vec::new();
+        match get_config(&client, &self.user, self.mainnet, self.strict) {
+            Ok(validator) => {
+                checks.push(UserCheck::new("parse", Ok(())));
+                if validator.proof_of_possession.is_some() {
+                    checks.push(UserCheck::new(
+                        "proof_of_possession",
+                        check_proof_of_possession(&validator),
+                    ));
+                }
+                checks.push(UserCheck::new(
+                    "hosts_and_keys",
+                    check_hosts_and_keys(&validator),
+                ));
+                let genesis_checks = if self.mainnet {
+                    load_balances(&client).and_then(|(accounts, _)| {
+                        let initialized_accounts: BTreeMap<AccountAddress, u64> = accounts
+                            .iter()
+                            .map(|inner| (inner.account_address, inner.balance))
+                            .collect();
+                        validate_validators(
+                            &layout,
+                            std::slice::from_ref(&validator),
+                            &initialized_accounts,
+                            false,
+                        )
+                    })
+                } else if validator.join_during_genesis {
+                    check_joining_stake(0, &validator)
+                } else {
+                    Ok(())
+                };
+                checks.push(UserCheck::new("genesis", genesis_checks));
+            }
+            Err(err) => checks.push(UserCheck::new("parse", Err(err))),
+        }
+
+        Ok(UserValidationReport {
+            user: self.user,
+            passes: checks.iter().all(|check| check.passed),
+            checks,
+        })
+    }
+}
+
+impl ValidateUser {
+    /// Same as `execute_serialized`, but fails if any check does
+    ///
+    /// The report is printed either way, so every failed check can be fixed in one pass.
+    async fn execute_serialized_report(self) -> CliResult {
+        let command_name = self.command_name();
+        start_logger();
+        let start_time = Instant::now();
+        let result = self.execute().await;
+        let passes = result.as_ref().map_or(false, |report| report.passes);
+        let output = to_common_result(command_name, start_time, result).await?;
+        if passes {
+            Ok(output)
+        } else {
+            Err(output)
+        }
+    }
+}
+
+/// Checks that the consensus proof of possession was made with the consensus key, which genesis
+/// doesn't check until the validator is added to the validator set
+fn check_proof_of_possession(validator: &ValidatorConfiguration) -> CliTypedResult<()> {
+    if let (Some(consensus_public_key), Some(proof_of_possession)) = (
+        validator.consensus_public_key.as_ref(),
+        validator.proof_of_possession.as_ref(),
+    ) {
+        proof_of_possession
+            .verify(consensus_public_key)
+            .map_err(|err| {
+                CliError::UnexpectedError(format!(
+                    "consensus_proof_of_possession is not valid for consensus_public_key {}: {}",
+                    consensus_public_key, err
+                ))
+            })?;
+    }
+    Ok(())
+}
+
+/// Checks that a validator's validator and full node don't share a host or network key
+fn check_hosts_and_keys(validator: &ValidatorConfiguration) -> CliTypedResult<()> {
+    if let (Some(validator_host), Some(full_node_host)) = (
+        validator.validator_host.as_ref(),
+        validator.full_node_host.as_ref(),
+    ) {
+        if validator_host == full_node_host {
+            return Err(CliError::UnexpectedError(format!(
+                "validator_host and full_node_host are both {}:{}",
+                validator_host.host, validator_host.port
+            )));
+        }
+    }
+    if let (Some(validator_key), Some(full_node_key)) = (
+        validator.validator_network_public_key.as_ref(),
+        validator.full_node_network_public_key.as_ref(),
+    ) {
+        if validator_key == full_node_key {
+            return Err(CliError::UnexpectedError(format!(
+                "validator_network_public_key and full_node_network_public_key are both {}",
+                validator_key
+            )));
+        }
+    }
+    if validator.full_node_host.is_some() != validator.full_node_network_public_key.is_some() {
+        return Err(CliError::UnexpectedError(
+            "full_node_host and full_node_network_public_key must be set together".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Find the employee pools that an address is in
+///
+/// Searches the employee vesting accounts file for the address as an employee account, or as the
+/// owner, operator, voter, or beneficiary resetter of a pool.
+#[derive(Parser)]
+pub struct FindEmployeeAccount {
+    /// Address to look for
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) address: AccountAddress,
+
+    #[clap(flatten)]
+    pub(crate) git_options: GitOptions,
+}
+
+/// A role that an address has in an employee pool
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EmployeeAccountMatch {
+    /// Index of the pool in the employee vesting accounts file
+    pub pool_index: usize,
+    /// One of `account`, `owner`, `operator`, `voter`, or `beneficiary_resetter`
+    pub role: String,
+    pub stake_amount: u64,
+    pub operator_account_address: AccountAddress,
+}
+
+#[async_trait]
+impl CliCommand<Vec<EmployeeAccountMatch>> for FindEmployeeAccount {
+    fn command_name(&self) -> &'static str {
+        "FindEmployeeAccount"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<EmployeeAccountMatch>> {
+        let client = self.git_options.get_client()?;
+        let employee_vesting_accounts: EmployeePoolMap =
+            client.get(Path::new(EMPLOYEE_VESTING_ACCOUNTS_FILE))?;
+
+        let mut matches = Vec::new();
+        for (pool_index, pool) in employee_vesting_accounts.inner.iter().enumerate() {
+            let validator = &pool.validator;
+            let roles = [
+                ("account", pool.accounts.contains(&self.address)),
+                ("owner", validator.owner_account_address == self.address),
+                (
+                    "operator",
+                    validator.operator_account_address == self.address,
+                ),
+                ("voter", validator.voter_account_address == self.address),
+                (
+                    "beneficiary_resetter",
+                    pool.beneficiary_resetter == self.address,
+                ),
+            ];
+            for (role, _) in roles.iter().filter(|(_, found)| *found) {
+                matches.push(EmployeeAccountMatch {
+                    pool_index,
+                    role: role.to_string(),
+                    stake_amount: validator.stake_amount,
+                    operator_account_address: validator.operator_account_address,
+                });
+            }
+        }
+
+        if matches.is_empty() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Address {} is not in any of the {} employee pools in {}",
+                self.address,
+                employee_vesting_accounts.inner.len(),
+                EMPLOYEE_VESTING_ACCOUNTS_FILE
+            )));
+        }
+        Ok(matches)
+    }
+}
+
+/// Export every validator in the genesis repository to a CSV file
+///
+/// The repository is validated the same way as `GenerateGenesis` first, so the export matches
+/// what genesis would contain.  Employee pool validators are included for mainnet.  Public keys
+/// are abbreviated, since the export is for review in a spreadsheet.
+#[derive(Parser)]
+pub struct ExportValidatorsCsv {
+    /// Path of the CSV file to write
+    #[clap(long, parse(from_os_str))]
+    pub(crate) output_file: PathBuf,
+
+    /// Whether this is mainnet genesis
+    #[clap(long)]
+    pub(crate) mainnet: bool,
+
+    #[clap(flatten)]
+    pub(crate) genesis_options: GenesisOptions,
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+    #[clap(flatten)]
+    pub(crate) git_options: GitOptions,
+}
+
+const VALIDATORS_CSV_HEADER: [&str; 12] = [
+    "name",
+    "owner_account_address",
+    "operator_account_address",
+    "voter_account_address",
+    "stake_amount",
+    "commission_percentage",
+    "join_during_genesis",
+    "validator_host",
+    "full_node_host",
+    "consensus_public_key",
+    "validator_network_public_key",
+    "full_node_network_public_key",
+];
+
+#[async_trait]
+impl CliCommand<PathBuf> for ExportValidatorsCsv {
+    fn command_name(&self) -> &'static str {
+        "ExportValidatorsCsv"
+    }
+
+    async fn execute(self) -> CliTypedResult<PathBuf> {
+        check_if_file_exists(self.output_file.as_path(), self.prompt_options)?;
+        let client = self.git_options.get_client()?;
+
+        // Don't export a repository that genesis would reject
+        fetch_genesis_summary(&client, self.mainnet, &self.genesis_options)?;
+
+        let layout: Layout = client.get(Path::new(LAYOUT_FILE))?;
+        let validators =
+            get_validator_configs(&client, &layout, self.mainnet, self.genesis_options.strict)
+                .map_err(parse_error)?;
+        let employee_validators: Vec<_> = if self.mainnet {
+            let employee_vesting_accounts: EmployeePoolMap =
+                client.get(Path::new(EMPLOYEE_VESTING_ACCOUNTS_FILE))?;
+            employee_vesting_accounts
+                .inner
+                .into_iter()
+                .map(|pool| pool.validator)
+                .collect()
+        } else {
+            vec![]
+        };
+
+        let csv = validators_csv(&name_validators(&layout, &validators, &employee_validators));
+        write_to_file(self.output_file.as_path(), "validators CSV", csv.as_bytes())?;
+        Ok(self.output_file)
+    }
+}
+
+/// Formats validators as CSV with a header row, one row per validator
+fn validators_csv(validators: &[(String, &ValidatorConfiguration)]) -> String {
+    let host = |host: &Option<HostAndPort>| {
+        host.as_ref()
+            .map(|host| format!("{}:{}", host.host, host.port))
+            .unwrap_or_default()
+    };
+
+    let mut csv = VALIDATORS_CSV_HEADER.join(",");
+    csv.push('\n');
+    for (name, validator) in validators {
+        let row = [
+            name.clone(),
+            validator.owner_account_address.to_hex_literal(),
+            validator.operator_account_address.to_hex_literal(),
+            validator.voter_account_address.to_hex_literal(),
+            validator.stake_amount.to_string(),
+            validator.commission_percentage.to_string(),
+            validator.join_during_genesis.to_string(),
+            host(&validator.validator_host),
+            host(&validator.full_node_host),
+            validator
+                .consensus_public_key
+                .as_ref()
+                .map(abbreviate_key)
+                .unwrap_or_default(),
+            validator
+                .validator_network_public_key
+                .as_ref()
+                .map(abbreviate_key)
+                .unwrap_or_default(),
+            validator
+                .full_node_network_public_key
+                .as_ref()
+                .map(abbreviate_key)
+                .unwrap_or_default(),
+        ];
+        let fields: Vec<_> = row.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quotes a CSV field if it contains a separator, quote, or line break
+fn csv_field(field: &str) -> String {
+    if field.contains(|c| matches!(c, ',' | '"' | '\n' | '\r')) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Export the seed peers of the validators joining during genesis as a node config fragment
+///
+/// The fragment has the `seeds` of the validator network and the public full node network, in
+/// the same YAML format as the node config, so it can be merged into the nodes' configs.  Only
+/// published addresses are included, the same as the seeds written by `GenerateGenesis`.
+#[derive(Parser)]
+pub struct ExportNodeSeeds {
+    /// Path of the YAML file to write
+    #[clap(long, parse(from_os_str))]
+    pub(crate) output_file: PathBuf,
+
+    /// Whether this is mainnet genesis
+    #[clap(long)]
+    pub(crate) mainnet: bool,
+
+    #[clap(flatten)]
+    pub(crate) genesis_options: GenesisOptions,
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+    #[clap(flatten)]
+    pub(crate) git_options: GitOptions,
+}
+
+#[async_trait]
+impl CliCommand<PathBuf> for ExportNodeSeeds {
+    fn command_name(&self) -> &'static str {
+        "ExportNodeSeeds"
+    }
+
+    async fn execute(self) -> CliTypedResult<PathBuf> {
+        check_if_file_exists(self.output_file.as_path(), self.prompt_options)?;
+        let client = self.git_options.get_client()?;
+        let summary = fetch_genesis_summary(&client, self.mainnet, &self.genesis_options)?;
+
+        let config = NodeSeedsConfig::from(GenesisSeeds::from_summaries(&summary.validators));
+        write_to_file(
+            self.output_file.as_path(),
+            "node seeds config",
+            to_yaml(&config)?.as_bytes(),
+        )?;
+        Ok(self.output_file)
+    }
+}
+
+/// Validates the repository the same way as `GenerateGenesis`, returning only the summary
+fn fetch_genesis_summary(
+    client: &Client,
+    mainnet: bool,
+    genesis_options: &GenesisOptions,
+) -> CliTypedResult<GenesisSummary> {
+    if mainnet {
+        fetch_mainnet_genesis_info_from(client, genesis_options).map(|(_, summary)| summary)
+    } else {
+        fetch_genesis_info_from(client, genesis_options).map(|(_, summary)| summary)
+    }
+}
+
+/// Shortens a key to its first and last few bytes for display
+fn abbreviate_key<K: ValidCryptoMaterial>(key: &K) -> String {
+    let encoded = hex::encode(key.to_bytes());
+    if encoded.len() <= 16 {
+        format!("0x{}", encoded)
+    } else {
+        format!("0x{}..{}", &encoded[..8], &encoded[encoded.len() - 8..])
+    }
+}
+
+/// Format to print genesis stats in
+#[derive(Clone, Copy, Debug)]
+pub enum StatsFormat {
+    Table,
+    Json,
+}
+
+impl FromStr for StatsFormat {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(StatsFormat::Table),
+            "json" => Ok(StatsFormat::Json),
+            _ => Err("Invalid format, must be one of [table, json]"),
+        }
+    }
+}
+
+/// Print aggregate numbers about a candidate genesis
+///
+/// Stats are computed from the git repository with the same validation as `GenerateGenesis`, but
+/// without building the genesis transaction.  With `--genesis-file` they're instead read from the
+/// validator set in an existing genesis blob, which only holds the validators joining during
+/// genesis.
+#[derive(Parser)]
+pub struct Stats {
+    /// Whether this is mainnet genesis
+    #[clap(long)]
+    mainnet: bool,
+    /// Read stats from an existing genesis blob rather than the git repository
+    #[clap(long, parse(from_os_str))]
+    genesis_file: Option<PathBuf>,
+    /// Format to print the stats in [table, json]
+    #[clap(long, default_value = "table")]
+    format: StatsFormat,
+
+    #[clap(flatten)]
+    genesis_options: GenesisOptions,
+    #[clap(flatten)]
+    git_options: GitOptions,
+}
+
+impl Stats {
+    pub async fn execute(self) -> CliTypedResult<String> {
+        let stats = self.stats()?;
+        match self.format {
+            StatsFormat::Table => Ok(stats.to_table()),
+            StatsFormat::Json => serde_json::to_string_pretty(&stats)
+                .map_err(|err| CliError::UnexpectedError(err.to_string())),
+        }
+    }
+
+    fn stats(&self) -> CliTypedResult<GenesisStats> {
+        if let Some(ref genesis_file) = self.genesis_file {
+            let genesis: Transaction = bcs::from_bytes(&read_from_file(genesis_file)?)?;
+            return GenesisStats::from_genesis(&genesis);
+        }
+
+        let summary = if self.mainnet {
+            fetch_mainnet_genesis_info(self.git_options.clone(), &self.genesis_options)?.1
+        } else {
+            fetch_genesis_info(self.git_options.clone(), &self.genesis_options)?.1
+        };
+        Ok(GenesisStats::from_summary(&summary))
+    }
+}
+
+/// Retrieves all information for mainnet genesis from the Git repository
+pub fn fetch_mainnet_genesis_info(
+    git_options: GitOptions,
+    genesis_options: &GenesisOptions,
+) -> CliTypedResult<(MainnetGenesisInfo, GenesisSummary)> {
+    fetch_mainnet_genesis_info_from(&git_options.get_client()?, genesis_options)
+}
+
+/// Retrieves all information for mainnet genesis with an existing client
+fn fetch_mainnet_genesis_info_from(
+    client: &Client,
+    genesis_options: &GenesisOptions,
+) -> CliTypedResult<(MainnetGenesisInfo, GenesisSummary)> {
+    let layout = load_and_validate_layout(client, true)?;
+    let chain_id = resolve_chain_id(&layout, genesis_options, true)?;
+    // Already checked by the layout validation, but the error shouldn't depend on that
+    let total_supply = layout.total_supply.ok_or_else(|| {
+        CliError::UnexpectedError(format!(
+            "total_supply must be set in {} for mainnet",
+            LAYOUT_FILE
+        ))
+    })?;
+
+    let (mut accounts, balances_files) = load_balances(client)?;
+    if genesis_options.sort_accounts {
+        accounts.sort_by_key(|account| account.account_address);
+    }
+
+    // Check that the supply matches the total
+    let total_balance_supply: u64 = accounts.iter().map(|inner| inner.balance).sum();
+    if total_supply != total_balance_supply {
+        return Err(supply_mismatch_error(
+            &accounts,
+            &balances_files,
+            total_balance_supply,
+            total_supply,
+            genesis_options.debug_supply.as_deref(),
+        ));
+    }
+
+    // Keep track of accounts for later lookup of balances
+    let initialized_accounts: BTreeMap<AccountAddress, u64> = accounts
+        .iter()
+        .map(|inner| (inner.account_address, inner.balance))
+        .collect();
+
+    let mut employee_vesting_accounts: EmployeePoolMap =
+        client.get(Path::new(EMPLOYEE_VESTING_ACCOUNTS_FILE))?;
+    resolve_validator_hosts(
+        employee_vesting_accounts
+            .inner
+            .iter_mut()
+            .map(|inner| &mut inner.validator),
+        genesis_options,
+    )?;
+
+    let employee_validators: Vec<_> = employee_vesting_accounts
+        .inner
+        .iter()
+        .map(|inner| inner.validator.clone())
+        .collect();
+    let employee_vesting_accounts: Vec<EmployeePool> = employee_vesting_accounts.try_into()?;
+    let mut validators = get_validator_configs(client, &layout, true, genesis_options.strict)
+        .map_err(parse_error)?;
+    resolve_validator_hosts(validators.iter_mut(), genesis_options)?;
+
+    check_employee_accounts_unique(&employee_vesting_accounts)?;
+
+    // Check accounts for employee accounts
+    for (i, pool) in employee_vesting_accounts.iter().enumerate() {
+        let mut total_stake_pool_amount = 0;
+        for (j, account) in pool.accounts.iter().enumerate() {
+            if !initialized_accounts.contains_key(account) {
+                return Err(CliError::UnexpectedError(format!(
+                    "Account #{} '{}' in pool #{} is is not in the initialized balances",
+                    j, account, i
+                )));
+            }
+            total_stake_pool_amount += initialized_accounts.get(account).unwrap();
+        }
+
+        if total_stake_pool_amount != pool.validator.validator.stake_amount {
+            return Err(CliError::UnexpectedError(format!(
+                "Stake amount {} in pool #{} does not match combined of accounts {}",
+                pool.validator.validator.stake_amount, i, total_stake_pool_amount
+            )));
+        }
+
+        if !initialized_accounts.contains_key(&pool.validator.validator.owner_address) {
+            return Err(CliError::UnexpectedError(format!(
+                "Owner address {} in pool #{} is is not in the initialized balances",
+                pool.validator.validator.owner_address, i
+            )));
+        }
+        if !initialized_accounts.contains_key(&pool.validator.validator.operator_address) {
+            return Err(CliError::UnexpectedError(format!(
+                "Operator address {} in pool #{} is is not in the initialized balances",
+                pool.validator.validator.operator_address, i
+            )));
+        }
+        if !initialized_accounts.contains_key(&pool.validator.validator.voter_address) {
+            return Err(CliError::UnexpectedError(format!(
+                "Voter address {} in pool #{} is is not in the initialized balances",
+                pool.validator.validator.voter_address, i
+            )));
+        }
+        if !initialized_accounts.contains_key(&pool.beneficiary_resetter) {
+            return Err(CliError::UnexpectedError(format!(
+                "Beneficiary resetter {} in pool #{} is is not in the initialized balances",
+                pool.beneficiary_resetter, i
+            )));
+        }
+    }
+
+    validate_validators(&layout, &employee_validators, &initialized_accounts, true)?;
+    validate_validators(&layout, &validators, &initialized_accounts, false)?;
+    let named_validators = name_validators(&layout, &validators, &employee_validators);
+    check_consensus_keys(&named_validators)?;
+    check_host_collisions(&named_validators, genesis_options)?;
+    let staked = staked_supply(employee_validators.iter().chain(validators.iter()));
+    let staked_supply_bps = check_staked_supply(&layout, staked, total_supply, genesis_options)?;
+
+    let framework = client.get_framework()?;
+
+    // Report any balances that nothing else in genesis refers to, these are likely left over from
+    // withdrawn participants
+    let unreferenced_balances = find_unreferenced_balances(
+        &initialized_accounts,
+        &validators,
+        &employee_vesting_accounts,
+    );
+    if !unreferenced_balances.accounts.is_empty() {
+        let message = format!(
+            "{} accounts with a combined balance of {} are not referenced by any validator or employee pool: {}",
+            unreferenced_balances.accounts.len(),
+            unreferenced_balances.total_balance,
+            unreferenced_balances
+                .accounts
+                .iter()
+                .map(|(account, balance)| format!("{} ({})", account, balance))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        if genesis_options.fail_on_unreferenced_balances {
+            return Err(CliError::UnexpectedError(message));
+        }
+        warn!("{}", message);
+    }
+    let mut validator_summaries =
+        summarize_validators(employee_validators.iter().chain(validators.iter()))?;
+    for (i, validator) in validator_summaries
+        .iter_mut()
+        .take(employee_validators.len())
+        .enumerate()
+    {
+        validator.employee_pool = Some(i);
+    }
+    let nakamoto_coefficients = nakamoto_coefficients(&validator_summaries);
+    check_nakamoto_coefficients(&layout, nakamoto_coefficients, genesis_options)?;
+    let summary = GenesisSummary {
+        chain_id: Some(chain_id.id()),
+        validator_set_hash: Some(validator_set_hash(
+            employee_validators.iter().chain(validators.iter()),
+        )?),
+        staked_supply_bps: Some(staked_supply_bps),
+        total_supply: Some(total_supply),
+        nakamoto_coefficients,
+        commission_stats: commission_stats(&validator_summaries),
+        balances_files,
+        unreferenced_balances: Some(unreferenced_balances),
+        validators: validator_summaries,
+        framework: check_framework_version(&framework, genesis_options)?,
+    };
+    let genesis_info = MainnetGenesisInfo::new(
+        chain_id,
+        accounts,
+        employee_vesting_accounts,
+        validators,
+        framework,
+        &GenesisConfiguration {
+            allow_new_validators: true,
+            epoch_duration_secs: layout.epoch_duration_secs,
+            is_test: false,
+            min_stake: layout.min_stake,
+            min_voting_threshold: layout.min_voting_threshold,
+            max_stake: layout.max_stake,
+            recurring_lockup_duration_secs: layout.recurring_lockup_duration_secs,
+            required_proposer_stake: layout.required_proposer_stake,
+            rewards_apy_percentage: layout.rewards_apy_percentage,
+            voting_duration_secs: layout.voting_duration_secs,
+            voting_power_increase_limit: layout.voting_power_increase_limit,
+        },
+    )?;
+    Ok((genesis_info, summary))
+}
+
+/// Pairs each validator with a name to report it by, the user for validators in the layout and the
+/// pool index for employee pools
+fn name_validators<'a>(
+    layout: &Layout,
+    validators: &'a [ValidatorConfiguration],
+    employee_validators: &'a [ValidatorConfiguration],
+) -> Vec<(String, &'a ValidatorConfiguration)> {
+    // Validators are read in the same order as the users in the layout
+    layout
+        .users
+        .iter()
+        .map(|user| format!("user {}", user))
+        .zip(validators.iter())
+        .chain(
+            employee_validators
+                .iter()
+                .enumerate()
+                .map(|(i, validator)| (format!("employee pool #{}", i), validator)),
+        )
+        .collect()
+}
+
+/// Checks that no two validators share a consensus key, which would get both slashed for
+/// equivocation
+///
+/// This can't be turned off.
+fn check_consensus_keys(validators: &[(String, &ValidatorConfiguration)]) -> CliTypedResult<()> {
+    let mut key_users: BTreeMap<Vec<u8>, Vec<&str>> = BTreeMap::new();
+    for (name, validator) in validators {
+        if let Some(ref key) = validator.consensus_public_key {
+            key_users.entry(key.to_bytes()).or_default().push(name);
+        }
+    }
+
+    let reused: Vec<String> = key_users
+        .iter()
+        .filter(|(_, names)| names.len() > 1)
+        .map(|(key, names)| format!("0x{} is used by {}", hex::encode(key), names.join(", ")))
+        .collect();
+    if reused.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::UnexpectedError(format!(
+            "Consensus keys must not be shared between validators: {}",
+            reused.join("; ")
+        )))
+    }
+}
+
+/// Resolves the validator and full node hosts that are DNS names to IP addresses with
+/// `--resolve-hosts`, keeping the ports
+fn resolve_validator_hosts<'a>(
+    validators: impl IntoIterator<Item = &'a mut ValidatorConfiguration>,
+    genesis_options: &GenesisOptions,
+) -> CliTypedResult<()> {
+    if !genesis_options.resolve_hosts {
+        return Ok(());
+    }
+
+    for validator in validators {
+        let owner = validator.owner_account_address;
+        for host in [&mut validator.validator_host, &mut validator.full_node_host]
+            .into_iter()
+            .flatten()
+        {
+            let resolved = host.resolve().map_err(|err| {
+                CliError::UnexpectedError(format!(
+                    "Failed to resolve host {}:{} of validator {}: {}",
+                    host.host, host.port, owner, err
+                ))
+            })?;
+            if resolved != *host {
+                genesis_options.note(format!(
+                    "Resolved host {}:{} of validator {} to {}",
+                    host.host, host.port, owner, resolved.host
+                ));
+                *host = resolved;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that no validator's full node host is another validator's validator host, as peers
+/// could connect to the wrong node
+fn check_host_collisions(
+    validators: &[(String, &ValidatorConfiguration)],
+    genesis_options: &GenesisOptions,
+) -> CliTypedResult<()> {
+    let mut collisions = Vec::new();
+    for (i, (full_node_name, full_node_validator)) in validators.iter().enumerate() {
+        let full_node_host = if let Some(ref host) = full_node_validator.full_node_host {
+            host
+        } else {
+            continue;
+        };
+        for (j, (validator_name, validator)) in validators.iter().enumerate() {
+            if i != j && validator.validator_host.as_ref() == Some(full_node_host) {
+                collisions.push(format!(
+                    "Full node host {} of {} is the validator host of {}",
+                    full_node_host, full_node_name, validator_name
+                ));
+            }
+        }
+    }
+
+    if collisions.is_empty() {
+        return Ok(());
+    }
+    let message = format!(
+        "Validator hosts collide with full node hosts of other validators: {}",
+        collisions.join("; ")
+    );
+    if genesis_options.fail_on_host_collisions {
+        return Err(CliError::UnexpectedError(message));
+    }
+    warn!("{}", message);
+    Ok(())
+}
+
+/// Checks that no account is in more than one employee pool, as its balance would count toward
+/// the stake of each pool it's in
+fn check_employee_accounts_unique(employee_pools: &[EmployeePool]) -> CliTypedResult<()> {
+    let mut account_pools: BTreeMap<AccountAddress, Vec<usize>> = BTreeMap::new();
+    for (i, pool) in employee_pools.iter().enumerate() {
+        for account in &pool.accounts {
+            account_pools.entry(*account).or_default().push(i);
+        }
+    }
+
+    let duplicates: Vec<String> = account_pools
+        .iter()
+        .filter(|(_, pools)| pools.len() > 1)
+        .map(|(account, pools)| {
+            format!(
+                "Account {} is in employee pools {}",
+                account,
+                pools
+                    .iter()
+                    .map(|i| format!("#{}", i))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })
+        .collect();
+    if duplicates.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::UnexpectedError(format!(
+            "Employee pool accounts must be unique across pools: {}",
+            duplicates.join("; ")
+        )))
+    }
+}
+
+/// Reports how many operators hold a third and two thirds of the voting power, failing if it's
+/// under the layout's minimum
+fn check_nakamoto_coefficients(
+    layout: &Layout,
+    coefficients: Option<NakamotoCoefficients>,
+    genesis_options: &GenesisOptions,
+) -> CliTypedResult<()> {
+    let coefficients = match coefficients {
+        Some(coefficients) => coefficients,
+        None => return Ok(()),
+    };
+    genesis_options.note(format!(
+        "{} operators hold more than a third of the voting power, and {} more than two thirds",
+        coefficients.third, coefficients.two_thirds
+    ));
+
+    if let Some(min) = layout.min_nakamoto_coefficient_third {
+        if (coefficients.third as u64) < min {
+            return Err(CliError::UnexpectedError(format!(
+                "Nakamoto coefficient {} is under min_nakamoto_coefficient_third {}",
+                coefficients.third, min
+            )));
+        }
+    }
+    Ok(())
+}
 
-        if !initialized_accounts.contains_key(&pool.validator.validator.owner_address) {
+/// Prints the staked ratio of the total supply, and checks it against the layout's bounds
+///
+/// Returns the ratio in basis points, so the summary reports the same ratio that was checked.
+fn check_staked_supply(
+    layout: &Layout,
+    staked: u128,
+    total_supply: u64,
+    genesis_options: &GenesisOptions,
+) -> CliTypedResult<u64> {
+    let staked_supply_bps = staked_supply_bps(staked, total_supply)?;
+    genesis_options.note(format!(
+        "Validators joining during genesis stake {}.{:02}% of the total supply",
+        staked_supply_bps / 100,
+        staked_supply_bps % 100
+    ));
+
+    if let Some(min) = layout.min_staked_supply_percent {
+        if staked * 100 < min as u128 * total_supply as u128 {
             return Err(CliError::UnexpectedError(format!(
-                "Owner address {} in pool #{} is is not in the initialized balances",
-                pool.validator.validator.owner_address, i
+                "Staked supply {} of {} ({} bps) is under min_staked_supply_percent {}%",
+                staked, total_supply, staked_supply_bps, min
             )));
         }
-        if !initialized_accounts.contains_key(&pool.validator.validator.operator_address) {
+    }
+    if let Some(max) = layout.max_staked_supply_percent {
+        if staked * 100 > max as u128 * total_supply as u128 {
             return Err(CliError::UnexpectedError(format!(
-                "Operator address {} in pool #{} is is not in the initialized balances",
-                pool.validator.validator.operator_address, i
+                "Staked supply {} of {} ({} bps) is over max_staked_supply_percent {}%",
+                staked, total_supply, staked_supply_bps, max
             )));
         }
-        if !initialized_accounts.contains_key(&pool.validator.validator.voter_address) {
+    }
+    Ok(staked_supply_bps)
+}
+
+/// Loads the initial balances from either the balances file, or every file in the balances
+/// directory
+///
+/// Files in the directory can be YAML in the same form as the balances file, or CSV with an
+/// `address,balance` row per account.  An account can only be in one file.
+fn load_balances(
+    client: &Client,
+) -> CliTypedResult<(Vec<AccountBalance>, Vec<BalancesFileSummary>)> {
+    let balances_dir = Path::new(BALANCES_DIR);
+    let files = if let Some(files) = client.list_files(balances_dir)? {
+        if client.get_bytes(Path::new(BALANCES_FILE)).is_ok() {
             return Err(CliError::UnexpectedError(format!(
-                "Voter address {} in pool #{} is is not in the initialized balances",
-                pool.validator.validator.voter_address, i
+                "Only one of {} and the {} directory can be used for balances",
+                BALANCES_FILE, BALANCES_DIR
             )));
         }
-        if !initialized_accounts.contains_key(&pool.beneficiary_resetter) {
+        if files.is_empty() {
             return Err(CliError::UnexpectedError(format!(
-                "Beneficiary resetter {} in pool #{} is is not in the initialized balances",
-                pool.beneficiary_resetter, i
+                "Balances directory {} has no files",
+                BALANCES_DIR
             )));
         }
+        files
+    } else {
+        vec![PathBuf::from(BALANCES_FILE)]
+    };
+
+    let mut accounts = Vec::new();
+    let mut summaries = Vec::new();
+    let mut account_files: BTreeMap<AccountAddress, String> = BTreeMap::new();
+    for file in files {
+        let name = file.display().to_string();
+        let bytes = client.get_bytes(file.as_path())?;
+        let file_accounts: Vec<AccountBalance> =
+            match file.extension().and_then(|extension| extension.to_str()) {
+                Some("yaml") | Some("yml") => {
+                    let account_balance_map: AccountBalanceMap =
+                        from_yaml(&String::from_utf8(bytes.clone())?)?;
+                    account_balance_map.try_into()?
+                }
+                Some("csv") => parse_balances_csv(&name, &String::from_utf8(bytes.clone())?)?,
+                _ => {
+                    return Err(CliError::UnexpectedError(format!(
+                        "Balances file {} must be a .yaml or .csv file",
+                        name
+                    )))
+                }
+            };
+
+        for account in &file_accounts {
+            if let Some(other_file) = account_files.insert(account.account_address, name.clone()) {
+                return Err(CliError::UnexpectedError(format!(
+                    "Account {} is in both balances files {} and {}",
+                    account.account_address, other_file, name
+                )));
+            }
+        }
+
+        let total_balance = file_accounts
+            .iter()
+            .try_fold(0u64, |total, inner| total.checked_add(inner.balance))
+            .ok_or_else(|| {
+                CliError::UnexpectedError(format!(
+                    "Balances in balances file {} add up to more than {}",
+                    name,
+                    u64::MAX
+                ))
+            })?;
+        let summary = BalancesFileSummary {
+            file: name,
+            hash: file_hash(&bytes),
+            num_accounts: file_accounts.len(),
+            total_balance,
+        };
+        info!(
+            "Balances file {} has {} accounts with a combined balance of {}",
+            summary.file, summary.num_accounts, summary.total_balance
+        );
+        summaries.push(summary);
+        accounts.extend(file_accounts);
     }
+    Ok((accounts, summaries))
+}
 
-    validate_validators(&layout, &employee_validators, &initialized_accounts, true)?;
-    validate_validators(&layout, &validators, &initialized_accounts, false)?;
+/// Parses `address,balance` rows, with an optional header row
+///
+/// The balance is everything after the first comma, so it can use `,` separators too.
+fn parse_balances_csv(name: &str, contents: &str) -> CliTypedResult<Vec<AccountBalance>> {
+    let mut accounts = Vec::new();
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (i == 0 && line.eq_ignore_ascii_case("address,balance")) {
+            continue;
+        }
 
-    let framework = client.get_framework()?;
-    Ok(MainnetGenesisInfo::new(
-        layout.chain_id,
-        accounts,
-        employee_vesting_accounts,
-        validators,
-        framework,
-        &GenesisConfiguration {
-            allow_new_validators: true,
-            epoch_duration_secs: layout.epoch_duration_secs,
-            is_test: false,
-            min_stake: layout.min_stake,
-            min_voting_threshold: layout.min_voting_threshold,
-            max_stake: layout.max_stake,
-            recurring_lockup_duration_secs: layout.recurring_lockup_duration_secs,
-            required_proposer_stake: layout.required_proposer_stake,
-            rewards_apy_percentage: layout.rewards_apy_percentage,
-            voting_duration_secs: layout.voting_duration_secs,
-            voting_power_increase_limit: layout.voting_power_increase_limit,
-        },
-    )?)
+        let invalid_row = |reason: String| {
+            CliError::UnexpectedError(format!(
+                "Invalid row {} in balances file {}: {}",
+                i + 1,
+                name,
+                reason
+            ))
+        };
+        let (address, balance) = line
+            .split_once(',')
+            .ok_or_else(|| invalid_row("expected `address,balance`".to_string()))?;
+        accounts.push(AccountBalance {
+            account_address: AccountAddress::from_str(address.trim())
+                .map_err(|err| invalid_row(err.to_string()))?,
+            balance: parse_amount(balance).map_err(|err| invalid_row(err.to_string()))?,
+        });
+    }
+    Ok(accounts)
 }
 
-/// Retrieves all information for genesis from the Git repository
-pub fn fetch_genesis_info(git_options: GitOptions) -> CliTypedResult<GenesisInfo> {
-    let client = git_options.get_client()?;
-    let layout: Layout = client.get(Path::new(LAYOUT_FILE))?;
+/// Describes balances that don't add up to the total supply, with enough context to find the
+/// discrepancy
+///
+/// If `debug_file` is given, each account's balance is also written to it
+fn supply_mismatch_error(
+    accounts: &[AccountBalance],
+    balances_files: &[BalancesFileSummary],
+    total_balance_supply: u64,
+    total_supply: u64,
+    debug_file: Option<&Path>,
+) -> CliError {
+    let difference = if total_balance_supply > total_supply {
+        format!("{} over", total_balance_supply - total_supply)
+    } else {
+        format!("{} under", total_supply - total_balance_supply)
+    };
+    let range = match (
+        accounts.iter().map(|account| account.balance).min(),
+        accounts.iter().map(|account| account.balance).max(),
+    ) {
+        (Some(min), Some(max)) => format!(", with balances from {} to {}", min, max),
+        _ => String::new(),
+    };
+    let breakdown = match debug_file {
+        Some(debug_file) => match write_supply_breakdown(debug_file, accounts) {
+            Ok(()) => format!(".  Balances by account written to {}", debug_file.display()),
+            Err(err) => {
+                warn!("Unable to write balances by account: {}", err);
+                String::new()
+            }
+        },
+        None => String::new(),
+    };
 
-    if layout.root_key.is_none() {
-        return Err(CliError::UnexpectedError(
-            "Layout field root_key was not set.  Please provide a hex encoded Ed25519PublicKey."
-                .to_string(),
+    CliError::UnexpectedError(format!(
+        "Total supply seen {} doesn't match expected total supply {} ({}) across {} accounts{}.  \
+        Balances by file: {}{}",
+        total_balance_supply,
+        total_supply,
+        difference,
+        accounts.len(),
+        range,
+        balances_files
+            .iter()
+            .map(|file| format!("{} ({})", file.file, file.total_balance))
+            .collect::<Vec<_>>()
+            .join(", "),
+        breakdown
+    ))
+}
+
+/// Writes each account's balance as a balances CSV, sorted by account address
+fn write_supply_breakdown(path: &Path, accounts: &[AccountBalance]) -> CliTypedResult<()> {
+    let mut sorted: Vec<&AccountBalance> = accounts.iter().collect();
+    sorted.sort_by_key(|account| account.account_address);
+    let mut csv = String::from("address,balance\n");
+    for account in sorted {
+        csv.push_str(&format!(
+            "{},{}\n",
+            account.account_address.to_hex_literal(),
+            account.balance
         ));
     }
+    write_to_file(path, "Supply breakdown", csv.as_bytes())
+}
+
+/// Retrieves all information for genesis from the Git repository
+pub fn fetch_genesis_info(
+    git_options: GitOptions,
+    genesis_options: &GenesisOptions,
+) -> CliTypedResult<(GenesisInfo, GenesisSummary)> {
+    fetch_genesis_info_from(&git_options.get_client()?, genesis_options)
+}
 
-    let validators = get_validator_configs(&client, &layout, false).map_err(parse_error)?;
+/// Retrieves all information for genesis with an existing client
+fn fetch_genesis_info_from(
+    client: &Client,
+    genesis_options: &GenesisOptions,
+) -> CliTypedResult<(GenesisInfo, GenesisSummary)> {
+    let layout = load_and_validate_layout(client, false)?;
+    let chain_id = resolve_chain_id(&layout, genesis_options, false)?;
+
+    let mut validators = get_validator_configs(client, &layout, false, genesis_options.strict)
+        .map_err(parse_error)?;
+    resolve_validator_hosts(validators.iter_mut(), genesis_options)?;
+    // Validators aren't checked against the layout's stake bounds for test networks
+    for (i, validator) in validators.iter().enumerate() {
+        if validator.join_during_genesis {
+            check_joining_stake(i, validator)?;
+        }
+    }
+    let named_validators = name_validators(&layout, &validators, &[]);
+    check_consensus_keys(&named_validators)?;
+    check_host_collisions(&named_validators, genesis_options)?;
     let framework = client.get_framework()?;
-    Ok(GenesisInfo::new(
-        layout.chain_id,
+    let validator_summaries = summarize_validators(&validators)?;
+    let summary = GenesisSummary {
+        chain_id: Some(chain_id.id()),
+        validator_set_hash: Some(validator_set_hash(&validators)?),
+        nakamoto_coefficients: nakamoto_coefficients(&validator_summaries),
+        commission_stats: commission_stats(&validator_summaries),
+        validators: validator_summaries,
+        framework: check_framework_version(&framework, genesis_options)?,
+        ..Default::default()
+    };
+    let genesis_info = GenesisInfo::new(
+        chain_id,
         layout.root_key.unwrap(),
         validators,
         framework,
@@ -262,7 +2166,121 @@ pub fn fetch_genesis_info(git_options: GitOptions) -> CliTypedResult<GenesisInfo
             voting_duration_secs: layout.voting_duration_secs,
             voting_power_increase_limit: layout.voting_power_increase_limit,
         },
-    )?)
+    )?;
+    Ok((genesis_info, summary))
+}
+
+/// Logs the versions of the framework packages, and checks the framework version if one is
+/// expected
+fn check_framework_version(
+    framework: &ReleaseBundle,
+    genesis_options: &GenesisOptions,
+) -> CliTypedResult<Vec<FrameworkPackageSummary>> {
+    let packages = summarize_framework(framework);
+    for package in &packages {
+        info!(
+            "Framework package {} version {} with source digest {}",
+            package.name,
+            package.version.as_deref().unwrap_or("unknown"),
+            package.source_digest
+        );
+    }
+
+    if let Some(ref expected_version) = genesis_options.expected_framework_version {
+        let version = packages
+            .iter()
+            .find(|package| package.name == FRAMEWORK_PACKAGE_NAME)
+            .and_then(|package| package.version.as_ref());
+        if version != Some(expected_version) {
+            return Err(CliError::UnexpectedError(format!(
+                "Framework package {} has version {}, but version {} was expected.  The framework in the git repository may be stale",
+                FRAMEWORK_PACKAGE_NAME,
+                version.map(String::as_str).unwrap_or("unknown"),
+                expected_version
+            )));
+        }
+    }
+    Ok(packages)
+}
+
+/// Reads the layout file and runs every check that only depends on the layout
+///
+/// Mainnet needs `total_supply` to check the balances against, and other networks need a
+/// `root_key`.
+pub fn load_and_validate_layout(client: &Client, mainnet: bool) -> CliTypedResult<Layout> {
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE))?;
+    let mut errors = layout.validate().err().unwrap_or_default();
+
+    let mut users = BTreeSet::new();
+    let duplicates: BTreeSet<_> = layout
+        .users
+        .iter()
+        .filter(|user| !users.insert(*user))
+        .collect();
+    if !duplicates.is_empty() {
+        errors.push(format!(
+            "users are listed more than once: {}",
+            duplicates
+                .iter()
+                .map(|user| user.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    if mainnet {
+        if layout.total_supply.is_none() {
+            errors.push("total_supply must be set for mainnet".to_string());
+        }
+    } else if layout.root_key.is_none() {
+        errors.push(
+            "root_key was not set.  Please provide a hex encoded Ed25519PublicKey.".to_string(),
+        );
+    }
+
+    if errors.is_empty() {
+        Ok(layout)
+    } else {
+        Err(CliError::UnexpectedError(format!(
+            "Layout file {} is invalid: {}",
+            LAYOUT_FILE,
+            errors.join("; ")
+        )))
+    }
+}
+
+/// Resolves the chain id to use, ensuring that a chain id reserved for a named network isn't
+/// accidentally used for a different kind of network
+fn resolve_chain_id(
+    layout: &Layout,
+    genesis_options: &GenesisOptions,
+    is_mainnet: bool,
+) -> CliTypedResult<ChainId> {
+    let chain_id = genesis_options.chain_id.unwrap_or(layout.chain_id);
+
+    if !genesis_options.allow_reserved_chain_id {
+        let mismatch = match NamedChain::from_chain_id(&chain_id) {
+            Ok(NamedChain::MAINNET) if !is_mainnet => Some("this is not a mainnet genesis"),
+            Ok(NamedChain::TESTNET) | Ok(NamedChain::PREMAINNET)
+                if !is_mainnet && layout.is_test =>
+            {
+                Some("the layout has `is_test` set")
+            }
+            _ => None,
+        };
+        if let Some(mismatch) = mismatch {
+            return Err(CliError::CommandArgumentError(format!(
+                "Chain id {} is reserved for {}, but {}.  Tooling may mistake this network for {}, use --allow-reserved-chain-id if this is intended",
+                chain_id.id(),
+                chain_id,
+                mismatch,
+                chain_id
+            )));
+        }
+    }
+
+    genesis_options.note(format!("Using chain id {} ({})", chain_id.id(), chain_id));
+    Ok(chain_id)
 }
 
 fn parse_error(errors: Vec<String>) -> CliError {
@@ -277,107 +2295,210 @@ fn get_validator_configs(
     client: &Client,
     layout: &Layout,
     is_mainnet: bool,
+    strict: bool,
 ) -> Result<Vec<ValidatorConfiguration>, Vec<String>> {
+    let (validators, errors) = get_validator_configs_partial(client, layout, is_mainnet, strict);
+    if errors.is_empty() {
+        Ok(validators)
+    } else {
+        Err(errors.iter().map(ToString::to_string).collect())
+    }
+}
+
+/// A user whose validator configuration couldn't be loaded
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GenesisParseError {
+    pub user: String,
+    pub error: String,
+}
+
+impl std::fmt::Display for GenesisParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.user, self.error)
+    }
+}
+
+/// Loads every user's validator configuration, returning the ones that loaded along with the
+/// failures of the rest
+///
+/// Validators are in the same order as the users in the layout, skipping the failed users.
+pub fn get_validator_configs_partial(
+    client: &Client,
+    layout: &Layout,
+    is_mainnet: bool,
+    strict: bool,
+) -> (Vec<ValidatorConfiguration>, Vec<GenesisParseError>) {
     let mut validators = Vec::new();
     let mut errors = Vec::new();
     for user in &layout.users {
-        match get_config(client, user, is_mainnet) {
+        match get_config(client, user, is_mainnet, strict) {
             Ok(validator) => {
                 validators.push(validator);
             }
             Err(failure) => {
-                if let CliError::UnexpectedError(failure) = failure {
-                    errors.push(format!("{}: {}", user, failure));
+                let error = if let CliError::UnexpectedError(failure) = failure {
+                    failure
                 } else {
-                    errors.push(format!("{}: {:?}", user, failure));
-                }
+                    format!("{:?}", failure)
+                };
+                errors.push(GenesisParseError {
+                    user: user.clone(),
+                    error,
+                });
             }
         }
     }
-
-    if errors.is_empty() {
-        Ok(validators)
-    } else {
-        Err(errors)
-    }
+    (validators, errors)
 }
 
 /// Do proper parsing so more information is known about failures
+///
+/// Every field of a file is parsed before failing, so that all problems with a file are reported
+/// together.
 fn get_config(
     client: &Client,
     user: &str,
     is_mainnet: bool,
+    strict: bool,
 ) -> CliTypedResult<ValidatorConfiguration> {
     // Load a user's configuration files
+    // Each can be either YAML or JSON encoded
     let dir = PathBuf::from(user);
-    let owner_file = dir.join(OWNER_FILE);
+    let owner_file = client.config_file(&dir, OWNER_FILE, OWNER_JSON_FILE)?;
     let owner_file = owner_file.as_path();
     let owner_config = client.get::<StringOwnerConfiguration>(owner_file)?;
+    let owner_source = ConfigSource {
+        user,
+        role: OWNER_ROLE,
+        file: owner_file,
+    };
 
     // Check and convert fields in owner file
-    let owner_account_address = parse_required_option(
-        &owner_config.owner_account_address,
-        owner_file,
-        "owner_account_address",
-        AccountAddress::from_str,
-    )?;
-    let owner_account_public_key = parse_required_option(
-        &owner_config.owner_account_public_key,
-        owner_file,
-        "owner_account_public_key",
-        Ed25519PublicKey::from_encoded_string,
-    )?;
-
-    let operator_account_address = parse_required_option(
-        &owner_config.operator_account_address,
-        owner_file,
-        "operator_account_address",
-        AccountAddress::from_str,
-    )?;
-    let operator_account_public_key = parse_required_option(
-        &owner_config.operator_account_public_key,
-        owner_file,
-        "operator_account_public_key",
-        Ed25519PublicKey::from_encoded_string,
-    )?;
-
-    let voter_account_address = parse_required_option(
-        &owner_config.voter_account_address,
-        owner_file,
-        "voter_account_address",
-        AccountAddress::from_str,
-    )?;
-    let voter_account_public_key = parse_required_option(
-        &owner_config.voter_account_public_key,
-        owner_file,
-        "voter_account_public_key",
-        Ed25519PublicKey::from_encoded_string,
-    )?;
-
-    let stake_amount = parse_required_option(
-        &owner_config.stake_amount,
-        owner_file,
-        "stake_amount",
-        u64::from_str,
-    )?;
-
-    // Default to 0 for commission percentage if missing.
-    let commission_percentage = parse_optional_option(
-        &owner_config.commission_percentage,
-        owner_file,
-        "commission_percentage",
-        u64::from_str,
-    )?
-    .unwrap_or(0);
-
-    // Default to true for whether the validator should be joining during genesis.
-    let join_during_genesis = parse_optional_option(
-        &owner_config.join_during_genesis,
-        owner_file,
-        "join_during_genesis",
-        bool::from_str,
-    )?
-    .unwrap_or(true);
+    let mut owner_errors = FieldErrors::new(&owner_source);
+    let owner_fields = (
+        owner_errors.parse_required(
+            &owner_config.owner_account_address,
+            "owner_account_address",
+            parse_account_address,
+        ),
+        owner_errors.parse_required(
+            &owner_config.owner_account_public_key,
+            "owner_account_public_key",
+            AccountPublicKey::from_encoded_string,
+        ),
+        owner_errors.parse_required(
+            &owner_config.operator_account_address,
+            "operator_account_address",
+            parse_account_address,
+        ),
+        owner_errors.parse_required(
+            &owner_config.operator_account_public_key,
+            "operator_account_public_key",
+            AccountPublicKey::from_encoded_string,
+        ),
+        owner_errors.parse_required(
+            &owner_config.voter_account_address,
+            "voter_account_address",
+            parse_account_address,
+        ),
+        owner_errors.parse_required(
+            &owner_config.voter_account_public_key,
+            "voter_account_public_key",
+            AccountPublicKey::from_encoded_string,
+        ),
+        owner_errors.parse_required(&owner_config.stake_amount, "stake_amount", parse_amount),
+        owner_errors.parse_optional(
+            &owner_config.commission_percentage,
+            "commission_percentage",
+            parse_number,
+        ),
+        owner_errors.parse_optional(
+            &owner_config.join_during_genesis,
+            "join_during_genesis",
+            bool::from_str,
+        ),
+    );
+    let (
+        owner_account_address,
+        owner_account_public_key,
+        operator_account_address,
+        operator_account_public_key,
+        voter_account_address,
+        voter_account_public_key,
+        stake_amount,
+        commission_percentage,
+        join_during_genesis,
+    ) = match owner_fields {
+        (
+            Some(owner_account_address),
+            Some(owner_account_public_key),
+            Some(operator_account_address),
+            Some(operator_account_public_key),
+            Some(voter_account_address),
+            Some(voter_account_public_key),
+            Some(stake_amount),
+            Some(commission_percentage),
+            Some(join_during_genesis),
+        ) => (
+            owner_account_address,
+            owner_account_public_key,
+            operator_account_address,
+            operator_account_public_key,
+            voter_account_address,
+            voter_account_public_key,
+            stake_amount,
+            // Default to 0 for commission percentage if missing.
+            commission_percentage.unwrap_or(0),
+            // Default to true for whether the validator should be joining during genesis.
+            join_during_genesis.unwrap_or(true),
+        ),
+        _ => return Err(owner_errors.into_error()),
+    };
+    // Accounts are created at genesis, so their keys can't have been rotated yet.  A key that
+    // doesn't derive its address is usually a copy-paste mistake, and would fail genesis later, so
+    // it's only an error here with `--strict`.
+    for (field_name, address, public_key) in [
+        (
+            "owner_account_address",
+            owner_account_address,
+            &owner_account_public_key,
+        ),
+        (
+            "operator_account_address",
+            operator_account_address,
+            &operator_account_public_key,
+        ),
+        (
+            "voter_account_address",
+            voter_account_address,
+            &voter_account_public_key,
+        ),
+    ] {
+        let derived_address = public_key.authentication_key().derived_address();
+        if address != derived_address {
+            let message = format!(
+                "{} {} does not match the address {} derived from its public key",
+                field_name, address, derived_address
+            );
+            if strict {
+                owner_errors.errors.push(message);
+            } else {
+                warn!("{} in {}", message, owner_source);
+            }
+        }
+    }
+    if !owner_errors.errors.is_empty() {
+        return Err(owner_errors.into_error());
+    }
+    // Echo amounts that weren't written as plain octas, so they can be double checked
+    if let Some(written) = owner_config.stake_amount.as_ref() {
+        if written.trim() != stake_amount.to_string() {
+            info!(
+                "stake_amount '{}' in {} is {} octas",
+                written, owner_source, stake_amount
+            );
+        }
+    }
 
     // We don't require the operator file if the validator is not joining during genesis.
     if is_mainnet && !join_during_genesis {
@@ -397,71 +2518,114 @@ fn get_config(
             stake_amount,
             commission_percentage,
             join_during_genesis,
+            publish_validator_address: true,
         });
     };
 
-    let operator_file = dir.join(OPERATOR_FILE);
+    let operator_file = client.config_file(&dir, OPERATOR_FILE, OPERATOR_JSON_FILE)?;
     let operator_file = operator_file.as_path();
     let operator_config = client.get::<StringOperatorConfiguration>(operator_file)?;
+    let operator_source = ConfigSource {
+        user,
+        role: OPERATOR_ROLE,
+        file: operator_file,
+    };
 
     // Check and convert fields in operator file
-    let operator_account_address_from_file = parse_required_option(
-        &operator_config.operator_account_address,
-        operator_file,
-        "operator_account_address",
-        AccountAddress::from_str,
-    )?;
-    let operator_account_public_key_from_file = parse_required_option(
-        &operator_config.operator_account_public_key,
-        operator_file,
-        "operator_account_public_key",
-        Ed25519PublicKey::from_encoded_string,
-    )?;
-    let consensus_public_key = parse_required_option(
-        &operator_config.consensus_public_key,
-        operator_file,
-        "consensus_public_key",
-        bls12381::PublicKey::from_encoded_string,
-    )?;
-    let consensus_proof_of_possession = parse_required_option(
-        &operator_config.consensus_proof_of_possession,
-        operator_file,
-        "consensus_proof_of_possession",
-        bls12381::ProofOfPossession::from_encoded_string,
-    )?;
-    let validator_network_public_key = parse_required_option(
-        &operator_config.validator_network_public_key,
-        operator_file,
-        "validator_network_public_key",
-        x25519::PublicKey::from_encoded_string,
-    )?;
-    let full_node_network_public_key = parse_optional_option(
-        &operator_config.full_node_network_public_key,
-        operator_file,
-        "full_node_network_public_key",
-        x25519::PublicKey::from_encoded_string,
-    )?;
+    let mut operator_errors = FieldErrors::new(&operator_source);
+    let operator_fields = (
+        operator_errors.parse_required(
+            &operator_config.operator_account_address,
+            "operator_account_address",
+            parse_account_address,
+        ),
+        operator_errors.parse_required(
+            &operator_config.operator_account_public_key,
+            "operator_account_public_key",
+            AccountPublicKey::from_encoded_string,
+        ),
+        operator_errors.parse_required(
+            &operator_config.consensus_public_key,
+            "consensus_public_key",
+            bls12381::PublicKey::from_encoded_string,
+        ),
+        operator_errors.parse_required(
+            &operator_config.consensus_proof_of_possession,
+            "consensus_proof_of_possession",
+            bls12381::ProofOfPossession::from_encoded_string,
+        ),
+        operator_errors.parse_required(
+            &operator_config.validator_network_public_key,
+            "validator_network_public_key",
+            x25519::PublicKey::from_encoded_string,
+        ),
+        operator_errors.parse_required(
+            &operator_config.validator_host,
+            "validator_host",
+            HostAndPort::from_str,
+        ),
+        operator_errors.parse_optional(
+            &operator_config.full_node_network_public_key,
+            "full_node_network_public_key",
+            x25519::PublicKey::from_encoded_string,
+        ),
+        operator_errors.parse_optional(
+            &operator_config.publish_validator_address,
+            "publish_validator_address",
+            bool::from_str,
+        ),
+    );
+    let (
+        operator_account_address_from_file,
+        operator_account_public_key_from_file,
+        consensus_public_key,
+        consensus_proof_of_possession,
+        validator_network_public_key,
+        validator_host,
+        full_node_network_public_key,
+        publish_validator_address,
+    ) = match operator_fields {
+        (
+            Some(operator_account_address),
+            Some(operator_account_public_key),
+            Some(consensus_public_key),
+            Some(consensus_proof_of_possession),
+            Some(validator_network_public_key),
+            Some(validator_host),
+            Some(full_node_network_public_key),
+            Some(publish_validator_address),
+        ) => (
+            operator_account_address,
+            operator_account_public_key,
+            consensus_public_key,
+            consensus_proof_of_possession,
+            validator_network_public_key,
+            validator_host,
+            full_node_network_public_key,
+            // Default to publishing the validator address alongside the full node address.
+            publish_validator_address.unwrap_or(true),
+        ),
+        _ => return Err(operator_errors.into_error()),
+    };
 
     // Verify owner & operator agree on operator
     if operator_account_address != operator_account_address_from_file {
-        return Err(
-            CliError::CommandArgumentError(
-                format!("Operator account {} in owner file {} does not match operator account {} in operator file {}",
-                        operator_account_address,
-                        owner_file.display(),
-                        operator_account_address_from_file,
-                        operator_file.display()
-                )));
+        return Err(CliError::CommandArgumentError(format!(
+            "Operator account {} in {} does not match operator account {} in {}",
+            operator_account_address,
+            owner_source,
+            operator_account_address_from_file,
+            operator_source
+        )));
     }
     if operator_account_public_key != operator_account_public_key_from_file {
-        return Err(
-            CliError::CommandArgumentError(
-                format!("Operator public key {} in owner file {} does not match operator public key {} in operator file {}",
-                        operator_account_public_key,
-                        owner_file.display(),
-                        operator_account_public_key_from_file,
-                        operator_file.display()
-                )));
+        return Err(CliError::CommandArgumentError(format!(
+            "Operator public key {} in {} does not match operator public key {} in {}",
+            operator_account_public_key,
+            owner_source,
+            operator_account_public_key_from_file,
+            operator_source
+        )));
     }
 
     // Build Validator configuration
@@ -475,59 +2639,158 @@ fn get_config(
         consensus_public_key: Some(consensus_public_key),
         proof_of_possession: Some(consensus_proof_of_possession),
         validator_network_public_key: Some(validator_network_public_key),
-        validator_host: Some(operator_config.validator_host),
+        validator_host: Some(validator_host),
         full_node_network_public_key,
         full_node_host: operator_config.full_node_host,
         stake_amount,
         commission_percentage,
         join_during_genesis,
+        publish_validator_address,
     })
 }
 
-fn parse_required_option<F: Fn(&str) -> Result<T, E>, T, E: std::fmt::Display>(
-    option: &Option<String>,
-    file: &Path,
-    field_name: &'static str,
-    parse: F,
-) -> Result<T, CliError> {
-    if let Some(ref field) = option {
-        parse(field).map_err(|err| {
-            CliError::CommandArgumentError(format!(
-                "Field {} is invalid in file {}.  Err: {}",
-                field_name,
-                file.display(),
-                err
-            ))
-        })
-    } else {
-        Err(CliError::CommandArgumentError(format!(
-            "File {} is missing {}",
-            file.display(),
-            field_name
-        )))
+/// Explains a key that's valid, but for a different field e.g. a network key pasted in as the
+/// consensus key
+fn misplaced_key_hint(field_name: &str, value: &str) -> Option<&'static str> {
+    let is_network_key = x25519::PublicKey::from_encoded_string(value).is_ok();
+    let is_consensus_key = bls12381::PublicKey::from_encoded_string(value).is_ok();
+    match field_name {
+        "consensus_public_key" if is_network_key => {
+            Some("This looks like a network or account key rather than a consensus key")
+        }
+        "validator_network_public_key" | "full_node_network_public_key" if is_consensus_key => {
+            Some("This looks like a consensus key rather than a network key")
+        }
+        _ => None,
     }
 }
 
-fn parse_optional_option<F: Fn(&str) -> Result<T, E>, T, E: std::fmt::Display>(
-    option: &Option<String>,
-    file: &Path,
-    field_name: &'static str,
-    parse: F,
-) -> Result<Option<T>, CliError> {
-    if let Some(ref field) = option {
-        parse(field)
-            .map_err(|err| {
-                CliError::CommandArgumentError(format!(
-                    "Field {} is invalid in file {}.  Err: {}",
-                    field_name,
-                    file.display(),
-                    err
-                ))
-            })
-            .map(Some)
-    } else {
-        Ok(None)
+const OWNER_ROLE: &str = "owner";
+const OPERATOR_ROLE: &str = "operator";
+
+/// The file a field was read from, so that errors say which user's owner or operator file to fix
+///
+/// Some fields, like the operator account, are in both files.
+struct ConfigSource<'a> {
+    user: &'a str,
+    role: &'static str,
+    file: &'a Path,
+}
+
+impl<'a> std::fmt::Display for ConfigSource<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "file {} ({} file of user {})",
+            self.file.display(),
+            self.role,
+            self.user
+        )
+    }
+}
+
+/// Collects the problems with each field of a file, so they can all be reported at once
+struct FieldErrors<'a> {
+    source: &'a ConfigSource<'a>,
+    errors: Vec<String>,
+}
+
+impl<'a> FieldErrors<'a> {
+    fn new(source: &'a ConfigSource<'a>) -> Self {
+        FieldErrors {
+            source,
+            errors: Vec::new(),
+        }
     }
+
+    /// Parses a field that must be present, returning `None` if it's missing or invalid
+    fn parse_required<F: Fn(&str) -> Result<T, E>, T, E: std::fmt::Display>(
+        &mut self,
+        option: &Option<String>,
+        field_name: &'static str,
+        parse: F,
+    ) -> Option<T> {
+        if option.is_none() {
+            self.errors.push(format!("{} is missing", field_name));
+            return None;
+        }
+        self.parse_optional(option, field_name, parse)
+            .and_then(|value| value)
+    }
+
+    /// Parses a field that may be left out, returning `None` only if it's invalid
+    fn parse_optional<F: Fn(&str) -> Result<T, E>, T, E: std::fmt::Display>(
+        &mut self,
+        option: &Option<String>,
+        field_name: &'static str,
+        parse: F,
+    ) -> Option<Option<T>> {
+        if let Some(ref field) = option {
+            // Values copied from a terminal or a YAML block scalar often pick up whitespace
+            let field = field.trim();
+            match parse(field) {
+                Ok(value) => Some(Some(value)),
+                Err(err) => {
+                    let hint = misplaced_key_hint(field_name, field)
+                        .map(|hint| format!(".  {}", hint))
+                        .unwrap_or_default();
+                    self.errors.push(format!(
+                        "{} is invalid with value '{}' ({}).  Err: {}{}",
+                        field_name,
+                        field,
+                        describe_value(field),
+                        err,
+                        hint
+                    ));
+                    None
+                }
+            }
+        } else {
+            Some(None)
+        }
+    }
+
+    fn into_error(self) -> CliError {
+        CliError::CommandArgumentError(format!(
+            "Missing or invalid fields in {}: {}",
+            self.source,
+            self.errors.join("; ")
+        ))
+    }
+}
+
+/// Parses an account address with or without the `0x` prefix, like the keys in the same files
+///
+/// All 64 hex characters are required, so a truncated address fails rather than being padded
+/// into a different account.
+fn parse_account_address(address: &str) -> Result<AccountAddress, AccountAddressParseError> {
+    AccountAddress::from_hex(address.strip_prefix("0x").unwrap_or(address))
+}
+
+/// Describes the shape of an invalid value, so that a truncated or padded copy stands out
+fn describe_value(value: &str) -> String {
+    match (value.chars().next(), value.chars().last()) {
+        (Some(first), Some(last)) => format!(
+            "{} characters, starting with '{}' and ending with '{}'",
+            value.chars().count(),
+            first,
+            last
+        ),
+        _ => "empty".to_string(),
+    }
+}
+
+/// Checks that a validator joining during genesis has stake, since it can't take part in consensus
+/// without voting power, even if the layout's `min_stake` is 0
+fn check_joining_stake(i: usize, validator: &ValidatorConfiguration) -> CliTypedResult<()> {
+    if validator.stake_amount == 0 {
+        return Err(CliError::UnexpectedError(format!(
+            "Validator #{} owner {} has a stake_amount of 0, though it's joining during genesis",
+            i,
+            validator.owner_account_address.to_hex_literal()
+        )));
+    }
+    Ok(())
 }
 
 fn validate_validators(
@@ -536,6 +2799,30 @@ fn validate_validators(
     initialized_accounts: &BTreeMap<AccountAddress, u64>,
     is_pooled_validator: bool,
 ) -> CliTypedResult<()> {
+    // Report every validator over the cap, so they can all be fixed at once
+    if let Some(max_commission_percentage) = layout.max_commission_percentage {
+        let over_cap: Vec<String> = validators
+            .iter()
+            .enumerate()
+            .filter(|(_, validator)| validator.commission_percentage > max_commission_percentage)
+            .map(|(i, validator)| {
+                format!(
+                    "#{} owner {} ({}%)",
+                    i,
+                    validator.owner_account_address.to_hex_literal(),
+                    validator.commission_percentage
+                )
+            })
+            .collect();
+        if !over_cap.is_empty() {
+            return Err(CliError::UnexpectedError(format!(
+                "Validators {} have commission over max_commission_percentage {}%",
+                over_cap.join(", "),
+                max_commission_percentage
+            )));
+        }
+    }
+
     // check accounts for validators
     for (i, validator) in validators.iter().enumerate() {
         if !initialized_accounts.contains_key(&validator.owner_account_address) {
@@ -583,6 +2870,7 @@ fn validate_validators(
 
         // Ensure that the validator is setup correctly if it's joining in genesis
         if validator.join_during_genesis {
+            check_joining_stake(i, validator)?;
             if validator.validator_network_public_key.is_none() {
                 return Err(CliError::UnexpectedError(format!(
                     "Validator #{} does not have a validator network public key, though it's joining during genesis",
@@ -613,6 +2901,13 @@ fn validate_validators(
                 validator.full_node_network_public_key.as_ref(),
             ) {
                 (None, None) => {
+                    // Something has to be published for others to connect to
+                    if !validator.publish_validator_address {
+                        return Err(CliError::UnexpectedError(format!(
+                            "Validator #{} does not publish its validator address, so it must have a full node host and public key",
+                            i
+                        )));
+                    }
                     info!("Validator #{} does not have a full node setup", i);
                 }
                 (Some(_), None) | (None, Some(_)) => {
@@ -0,0 +1,1118 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliError, CliTypedResult};
+use crate::genesis::git::GitSource;
+use aptos_config::config::{Peer, PeerRole};
+use aptos_config::network_id::NetworkId;
+use aptos_crypto::{HashValue, ValidCryptoMaterial};
+use aptos_genesis::config::{HostAndPort, ValidatorConfiguration};
+use aptos_types::{
+    access_path::{AccessPath, Path},
+    account_address::{from_identity_public_key, AccountAddress},
+    account_config::{ChainIdResource, CoinInfoResource},
+    network_address::{NetworkAddress, Protocol},
+    on_chain_config::{OnChainConfig, ValidatorSet},
+    state_store::state_key::StateKey,
+    transaction::{ChangeSet, Transaction, WriteSetPayload},
+    write_set::WriteOp,
+    PeerId,
+};
+use framework::{unzip_metadata_str, ReleaseBundle};
+use move_deps::move_core_types::{
+    ident_str, identifier::IdentStr, language_storage::ResourceKey, move_resource::MoveStructType,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use vm_genesis::EmployeePool;
+
+pub const SUMMARY_FILE: &str = "summary.yaml";
+pub const SEEDS_FILE: &str = "seeds.yaml";
+pub const MANIFEST_FILE: &str = "manifest.json";
+pub const FRAMEWORK_PACKAGE_NAME: &str = "AptosFramework";
+/// Basis points in one whole, i.e. 100%
+pub const BPS_PER_WHOLE: u128 = 10_000;
+/// Framework module holding the package registry
+const CODE_MODULE: &str = "code";
+/// Framework module holding the validator set and stake pools
+const STAKE_MODULE: &str = "stake";
+
+/// Summary of the inputs and checks that went into a generated genesis
+///
+/// This is written alongside the genesis blob and waypoint for review by ceremony participants.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct GenesisSummary {
+    /// Numeric chain id of the network
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u8>,
+    /// Hash of the validator set, see [`validator_set_hash`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validator_set_hash: Option<HashValue>,
+    /// Stake of the validators joining during genesis, in basis points of the total supply
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub staked_supply_bps: Option<u64>,
+    /// Total supply of coins in genesis, mainnet only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_supply: Option<u64>,
+    /// Decentralization of the voting power at genesis, see [`nakamoto_coefficients`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nakamoto_coefficients: Option<NakamotoCoefficients>,
+    /// Commission of the validators joining during genesis, see [`commission_stats`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commission_stats: Option<CommissionStats>,
+    /// Files the initial balances were read from
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub balances_files: Vec<BalancesFileSummary>,
+    /// Accounts in the balances file that aren't referenced by any validator or employee pool
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unreferenced_balances: Option<UnreferencedBalances>,
+    /// Validators in genesis along with their published addresses
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub validators: Vec<ValidatorSummary>,
+    /// Packages in the framework used for genesis
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub framework: Vec<FrameworkPackageSummary>,
+    /// Size of the genesis blob
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genesis_size: Option<GenesisSizeSummary>,
+}
+
+/// Size of the genesis blob, broken down by what it writes
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GenesisSizeSummary {
+    /// Size of the serialized genesis transaction
+    pub total_bytes: u64,
+    /// Framework modules and package metadata
+    pub framework_bytes: u64,
+    /// Framework share of the total, in basis points
+    pub framework_bps: u64,
+    /// Validator set and stake pool resources
+    pub validator_set_bytes: u64,
+    /// All other resources and table items e.g. accounts and their balances
+    pub resources_bytes: u64,
+    /// Size over which the blob is reported as over budget
+    pub budget_bytes: u64,
+    pub over_budget: bool,
+}
+
+/// Breaks down the size of the genesis transaction by the state it writes
+///
+/// Each write is counted with its key, so the sections add up to nearly the total.  The rest is
+/// events and encoding overhead.
+pub fn summarize_genesis_size(
+    genesis: &Transaction,
+    total_bytes: u64,
+    budget_bytes: u64,
+) -> CliTypedResult<GenesisSizeSummary> {
+    let change_set = genesis_change_set(genesis)?;
+
+    let mut framework_bytes = 0;
+    let mut validator_set_bytes = 0;
+    let mut resources_bytes = 0;
+    for write in change_set.write_set() {
+        let size = bcs::serialized_size(&write)? as u64;
+        let section = match write_section(write.0) {
+            WriteSection::Framework => &mut framework_bytes,
+            WriteSection::ValidatorSet => &mut validator_set_bytes,
+            WriteSection::Resources => &mut resources_bytes,
+        };
+        *section += size;
+    }
+
+    Ok(GenesisSizeSummary {
+        total_bytes,
+        framework_bytes,
+        framework_bps: if total_bytes == 0 {
+            0
+        } else {
+            (framework_bytes as u128 * BPS_PER_WHOLE / total_bytes as u128) as u64
+        },
+        validator_set_bytes,
+        resources_bytes,
+        budget_bytes,
+        over_budget: total_bytes > budget_bytes,
+    })
+}
+
+/// Part of genesis a state write belongs to
+enum WriteSection {
+    /// Framework modules and their package metadata
+    Framework,
+    ValidatorSet,
+    Resources,
+}
+
+fn write_section(state_key: &StateKey) -> WriteSection {
+    match state_key {
+        StateKey::AccessPath(access_path) => match access_path.get_path() {
+            Path::Code(_) => WriteSection::Framework,
+            Path::Resource(tag) if tag.module.as_str() == CODE_MODULE => WriteSection::Framework,
+            Path::Resource(tag) if tag.module.as_str() == STAKE_MODULE => {
+                WriteSection::ValidatorSet
+            }
+            Path::Resource(_) => WriteSection::Resources,
+        },
+        _ => WriteSection::Resources,
+    }
+}
+
+fn genesis_change_set(genesis: &Transaction) -> CliTypedResult<&ChangeSet> {
+    match genesis {
+        Transaction::GenesisTransaction(WriteSetPayload::Direct(change_set)) => Ok(change_set),
+        _ => Err(CliError::UnexpectedError(
+            "Genesis is not a direct write set transaction".to_string(),
+        )),
+    }
+}
+
+/// Decodes the validator set written by a genesis transaction
+pub fn genesis_validator_set(genesis: &Transaction) -> CliTypedResult<ValidatorSet> {
+    let change_set = genesis_change_set(genesis)?;
+    let validator_set = change_set
+        .write_set()
+        .into_iter()
+        .find_map(|(state_key, op)| match (state_key, op) {
+            (StateKey::AccessPath(access_path), WriteOp::Creation(value))
+            | (StateKey::AccessPath(access_path), WriteOp::Modification(value)) => {
+                match access_path.get_path() {
+                    Path::Resource(tag)
+                        if tag.module.as_str() == ValidatorSet::MODULE_IDENTIFIER
+                            && tag.name.as_str() == ValidatorSet::TYPE_IDENTIFIER =>
+                    {
+                        Some(value)
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .ok_or_else(|| {
+            CliError::UnexpectedError("Genesis does not write a validator set".to_string())
+        })?;
+    Ok(bcs::from_bytes(validator_set)?)
+}
+
+/// Total supply of the Aptos coin written by a genesis transaction, or `None` if the supply isn't
+/// tracked
+///
+/// A parallelizable supply is kept in an aggregator, which is written separately as a table item.
+pub fn genesis_total_supply(genesis: &Transaction) -> CliTypedResult<Option<u128>> {
+    let change_set = genesis_change_set(genesis)?;
+    let written = |expected_key: &StateKey| {
+        change_set
+            .write_set()
+            .into_iter()
+            .find_map(|(state_key, op)| match op {
+                WriteOp::Creation(value) | WriteOp::Modification(value)
+                    if state_key == expected_key =>
+                {
+                    Some(value)
+                }
+                _ => None,
+            })
+    };
+
+    let coin_info_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        AccountAddress::ONE,
+        CoinInfoResource::struct_tag(),
+    )));
+    let coin_info: CoinInfoResource =
+        bcs::from_bytes(written(&coin_info_key).ok_or_else(|| {
+            CliError::UnexpectedError("Genesis does not write the Aptos coin info".to_string())
+        })?)?;
+    let supply = match coin_info.supply() {
+        Some(supply) => supply,
+        None => return Ok(None),
+    };
+
+    if let Some(ref integer) = supply.integer {
+        Ok(Some(integer.value))
+    } else if let Some(ref aggregator) = supply.aggregator {
+        let value = written(&aggregator.state_key()).ok_or_else(|| {
+            CliError::UnexpectedError(
+                "Genesis does not write the Aptos coin supply aggregator".to_string(),
+            )
+        })?;
+        Ok(Some(bcs::from_bytes(value)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Framework configuration that a genesis transaction writes to 0x1, named like the layout
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GenesisFrameworkConfig {
+    pub chain_id: u8,
+    pub min_stake: u64,
+    pub max_stake: u64,
+    pub recurring_lockup_duration_secs: u64,
+    pub allow_new_validators: bool,
+    pub voting_power_increase_limit: u64,
+    pub min_voting_threshold: u128,
+    pub required_proposer_stake: u64,
+    pub voting_duration_secs: u64,
+}
+
+/// `0x1::staking_config::StakingConfig`, BCS is positional so only the field order matters
+#[derive(Deserialize)]
+struct StakingConfigResource {
+    minimum_stake: u64,
+    maximum_stake: u64,
+    recurring_lockup_duration_secs: u64,
+    allow_validator_set_change: bool,
+    _rewards_rate: u64,
+    _rewards_rate_denominator: u64,
+    voting_power_increase_limit: u64,
+}
+
+impl MoveStructType for StakingConfigResource {
+    const MODULE_NAME: &'static IdentStr = ident_str!("staking_config");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("StakingConfig");
+}
+
+/// `0x1::aptos_governance::GovernanceConfig`
+#[derive(Deserialize)]
+struct GovernanceConfigResource {
+    min_voting_threshold: u128,
+    required_proposer_stake: u64,
+    voting_duration_secs: u64,
+}
+
+impl MoveStructType for GovernanceConfigResource {
+    const MODULE_NAME: &'static IdentStr = ident_str!("aptos_governance");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("GovernanceConfig");
+}
+
+/// Decodes the chain id, staking config, and governance config written by a genesis transaction
+pub fn genesis_framework_config(genesis: &Transaction) -> CliTypedResult<GenesisFrameworkConfig> {
+    let change_set = genesis_change_set(genesis)?;
+    let chain_id: ChainIdResource = framework_resource(change_set)?;
+    let staking_config: StakingConfigResource = framework_resource(change_set)?;
+    let governance_config: GovernanceConfigResource = framework_resource(change_set)?;
+    Ok(GenesisFrameworkConfig {
+        chain_id: chain_id.chain_id().id(),
+        min_stake: staking_config.minimum_stake,
+        max_stake: staking_config.maximum_stake,
+        recurring_lockup_duration_secs: staking_config.recurring_lockup_duration_secs,
+        allow_new_validators: staking_config.allow_validator_set_change,
+        voting_power_increase_limit: staking_config.voting_power_increase_limit,
+        min_voting_threshold: governance_config.min_voting_threshold,
+        required_proposer_stake: governance_config.required_proposer_stake,
+        voting_duration_secs: governance_config.voting_duration_secs,
+    })
+}
+
+/// Decodes a resource that a genesis change set writes to the framework account
+fn framework_resource<T: MoveStructType + DeserializeOwned>(
+    change_set: &ChangeSet,
+) -> CliTypedResult<T> {
+    let key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        AccountAddress::ONE,
+        T::struct_tag(),
+    )));
+    let value = change_set
+        .write_set()
+        .into_iter()
+        .find_map(|(state_key, op)| match op {
+            WriteOp::Creation(value) | WriteOp::Modification(value) if state_key == &key => {
+                Some(value)
+            }
+            _ => None,
+        })
+        .ok_or_else(|| {
+            CliError::UnexpectedError(format!("Genesis does not write {} to 0x1", T::struct_tag()))
+        })?;
+    Ok(bcs::from_bytes(value)?)
+}
+
+/// Readable form of the genesis transaction, for review alongside the genesis blob
+///
+/// The framework is replaced by a hash of its writes, since the module bytecode isn't readable
+/// and would dwarf everything else.  Values are left as hex encoded BCS.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GenesisJson {
+    pub framework: FrameworkWrites,
+    pub writes: Vec<GenesisWrite>,
+    pub events: Vec<GenesisEvent>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FrameworkWrites {
+    /// SHA3-256 hash of the BCS encoded framework writes, in write set order
+    pub hash: HashValue,
+    pub num_writes: usize,
+    pub total_bytes: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GenesisWrite {
+    /// State key e.g. `0x1/resource/0x1::stake::ValidatorSet`
+    pub key: String,
+    /// One of `creation`, `modification`, or `deletion`
+    pub op: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GenesisEvent {
+    pub key: String,
+    pub sequence_number: u64,
+    pub type_tag: String,
+    pub data: String,
+}
+
+/// Converts the genesis transaction into its readable form
+pub fn genesis_to_json(genesis: &Transaction) -> CliTypedResult<GenesisJson> {
+    let change_set = genesis_change_set(genesis)?;
+
+    let mut framework_bytes = Vec::new();
+    let mut num_framework_writes = 0;
+    let mut writes = Vec::new();
+    for write in change_set.write_set() {
+        if let WriteSection::Framework = write_section(write.0) {
+            framework_bytes.extend(bcs::to_bytes(&write)?);
+            num_framework_writes += 1;
+            continue;
+        }
+
+        let (op, value) = match write.1 {
+            WriteOp::Creation(value) => ("creation", Some(value)),
+            WriteOp::Modification(value) => ("modification", Some(value)),
+            WriteOp::Deletion => ("deletion", None),
+        };
+        writes.push(GenesisWrite {
+            key: state_key_string(write.0),
+            op: op.to_string(),
+            value: value.map(|value| format!("0x{}", hex::encode(value))),
+        });
+    }
+
+    let events = change_set
+        .events()
+        .iter()
+        .map(|event| GenesisEvent {
+            key: event.key().to_string(),
+            sequence_number: event.sequence_number(),
+            type_tag: event.type_tag().to_string(),
+            data: format!("0x{}", hex::encode(event.event_data())),
+        })
+        .collect();
+
+    Ok(GenesisJson {
+        framework: FrameworkWrites {
+            hash: HashValue::sha3_256_of(&framework_bytes),
+            num_writes: num_framework_writes,
+            total_bytes: framework_bytes.len() as u64,
+        },
+        writes,
+        events,
+    })
+}
+
+fn state_key_string(state_key: &StateKey) -> String {
+    match state_key {
+        StateKey::AccessPath(access_path) => match access_path.get_path() {
+            Path::Code(module_id) => format!(
+                "{}/code/{}",
+                access_path.address.to_hex_literal(),
+                module_id
+            ),
+            Path::Resource(tag) => {
+                format!("{}/resource/{}", access_path.address.to_hex_literal(), tag)
+            }
+        },
+        StateKey::TableItem { handle, key } => {
+            format!("table/{}/0x{}", handle.0.to_hex_literal(), hex::encode(key))
+        }
+        StateKey::Raw(key) => format!("raw/0x{}", hex::encode(key)),
+    }
+}
+
+/// Identifies the version of a framework package
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FrameworkPackageSummary {
+    pub name: String,
+    /// Version in the package's manifest, if it could be read
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub upgrade_number: u64,
+    pub source_digest: String,
+}
+
+/// Summarizes each package in the framework
+pub fn summarize_framework(framework: &ReleaseBundle) -> Vec<FrameworkPackageSummary> {
+    framework
+        .packages
+        .iter()
+        .map(|package| {
+            let metadata = package.package_metadata();
+            FrameworkPackageSummary {
+                name: metadata.name.clone(),
+                version: manifest_version(&metadata.manifest),
+                upgrade_number: metadata.upgrade_number,
+                source_digest: metadata.source_digest.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Reads the package version out of a zipped Move.toml
+fn manifest_version(manifest: &[u8]) -> Option<String> {
+    let manifest: toml::Value = toml::from_str(&unzip_metadata_str(manifest).ok()?).ok()?;
+    manifest
+        .get("package")?
+        .get("version")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// A validator in genesis, with only the addresses the operator chose to publish
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ValidatorSummary {
+    pub owner_account_address: AccountAddress,
+    pub operator_account_address: AccountAddress,
+    pub stake_amount: u64,
+    pub commission_percentage: u64,
+    pub join_during_genesis: bool,
+    /// Index of the employee pool this validator stakes for, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub employee_pool: Option<usize>,
+    /// Validator network address, omitted if the operator keeps the validator private
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validator_network_address: Option<NetworkAddress>,
+    /// Full node network address, if the operator runs a full node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_node_network_address: Option<NetworkAddress>,
+}
+
+/// Deterministic hash of a validator set, so that coordinators can confirm they resolved the same
+/// validators from the same inputs
+///
+/// Validators are sorted by owner address before hashing, so the order they're read in doesn't
+/// matter.  Each validator is hashed as a `ValidatorHashEntry` after
+/// [`VALIDATOR_SET_HASH_DOMAIN`], so the hash doesn't depend on how `ValidatorConfiguration` is
+/// serialized.
+pub fn validator_set_hash<'a>(
+    validators: impl IntoIterator<Item = &'a ValidatorConfiguration>,
+) -> CliTypedResult<HashValue> {
+    let mut entries: Vec<_> = validators
+        .into_iter()
+        .map(ValidatorHashEntry::from)
+        .collect();
+    entries.sort_by_key(|entry| entry.owner_account_address);
+    let mut bytes = VALIDATOR_SET_HASH_DOMAIN.to_vec();
+    bytes.extend(bcs::to_bytes(&entries)?);
+    Ok(HashValue::sha3_256_of(&bytes))
+}
+
+/// Prefix of the bytes hashed by [`validator_set_hash`], versioned so a change to the encoding
+/// can't be mistaken for a different validator set
+pub const VALIDATOR_SET_HASH_DOMAIN: &[u8] = b"APTOS::GenesisValidatorSetHash::V1";
+
+/// Fixed encoding of a validator for [`validator_set_hash`]
+///
+/// Every field is always serialized in this order, with keys as their raw bytes.  BCS gives
+/// each `Option` a tag and each byte string a length, so different validators can't encode to
+/// the same bytes.  Fields must only be added with a new [`VALIDATOR_SET_HASH_DOMAIN`] version.
+#[derive(Serialize)]
+struct ValidatorHashEntry {
+    owner_account_address: AccountAddress,
+    owner_account_public_key: Vec<u8>,
+    operator_account_address: AccountAddress,
+    operator_account_public_key: Vec<u8>,
+    voter_account_address: AccountAddress,
+    voter_account_public_key: Vec<u8>,
+    consensus_public_key: Option<Vec<u8>>,
+    proof_of_possession: Option<Vec<u8>>,
+    validator_network_public_key: Option<Vec<u8>>,
+    validator_host: Option<(String, u16)>,
+    full_node_network_public_key: Option<Vec<u8>>,
+    full_node_host: Option<(String, u16)>,
+    stake_amount: u64,
+    commission_percentage: u64,
+    join_during_genesis: bool,
+    publish_validator_address: bool,
+}
+
+impl From<&ValidatorConfiguration> for ValidatorHashEntry {
+    fn from(validator: &ValidatorConfiguration) -> Self {
+        let host = |host: &Option<HostAndPort>| {
+            host.as_ref().map(|host| (host.host.to_string(), host.port))
+        };
+        ValidatorHashEntry {
+            owner_account_address: validator.owner_account_address,
+            owner_account_public_key: validator.owner_account_public_key.to_bytes(),
+            operator_account_address: validator.operator_account_address,
+            operator_account_public_key: validator.operator_account_public_key.to_bytes(),
+            voter_account_address: validator.voter_account_address,
+            voter_account_public_key: validator.voter_account_public_key.to_bytes(),
+            consensus_public_key: validator
+                .consensus_public_key
+                .as_ref()
+                .map(|key| key.to_bytes()),
+            proof_of_possession: validator
+                .proof_of_possession
+                .as_ref()
+                .map(|pop| pop.to_bytes()),
+            validator_network_public_key: validator
+                .validator_network_public_key
+                .as_ref()
+                .map(|key| key.to_bytes()),
+            validator_host: host(&validator.validator_host),
+            full_node_network_public_key: validator
+                .full_node_network_public_key
+                .as_ref()
+                .map(|key| key.to_bytes()),
+            full_node_host: host(&validator.full_node_host),
+            stake_amount: validator.stake_amount,
+            commission_percentage: validator.commission_percentage,
+            join_during_genesis: validator.join_during_genesis,
+            publish_validator_address: validator.publish_validator_address,
+        }
+    }
+}
+
+/// Combined stake of the validators joining during genesis
+pub fn staked_supply<'a>(validators: impl IntoIterator<Item = &'a ValidatorConfiguration>) -> u128 {
+    validators
+        .into_iter()
+        .filter(|validator| validator.join_during_genesis)
+        .map(|validator| validator.stake_amount as u128)
+        .sum()
+}
+
+/// Staked supply in basis points of the total supply, rounded down
+///
+/// This is only for display, bounds should be checked against the exact amounts.
+pub fn staked_supply_bps(staked: u128, total_supply: u64) -> CliTypedResult<u64> {
+    if total_supply == 0 {
+        return Err(CliError::UnexpectedError(
+            "Total supply must be greater than 0".to_string(),
+        ));
+    }
+    Ok((staked * BPS_PER_WHOLE / total_supply as u128) as u64)
+}
+
+/// Minimum number of operators that together hold more than a share of the voting power
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NakamotoCoefficients {
+    /// Operators needed to hold more than a third, enough to halt the chain
+    pub third: usize,
+    /// Operators needed to hold more than two thirds, enough to commit blocks on their own
+    pub two_thirds: usize,
+}
+
+/// Computes the Nakamoto coefficients of the validators joining during genesis
+///
+/// Validators are grouped by operator, since an operator runs all of its validators, including
+/// those of employee pools.  Returns `None` if no voting power joins during genesis.
+pub fn nakamoto_coefficients<'a>(
+    validators: impl IntoIterator<Item = &'a ValidatorSummary>,
+) -> Option<NakamotoCoefficients> {
+    let mut operator_stakes: BTreeMap<AccountAddress, u128> = BTreeMap::new();
+    for validator in validators
+        .into_iter()
+        .filter(|validator| validator.join_during_genesis)
+    {
+        *operator_stakes
+            .entry(validator.operator_account_address)
+            .or_default() += validator.stake_amount as u128;
+    }
+    let total: u128 = operator_stakes.values().sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut stakes: Vec<u128> = operator_stakes.into_values().collect();
+    stakes.sort_unstable_by(|a, b| b.cmp(a));
+    // Number of the largest operators whose combined stake is more than numerator / 3 of the total
+    let coefficient = |numerator: u128| {
+        let mut combined = 0;
+        for (i, stake) in stakes.iter().enumerate() {
+            combined += stake;
+            if combined * 3 > total * numerator {
+                return i + 1;
+            }
+        }
+        stakes.len()
+    };
+
+    Some(NakamotoCoefficients {
+        third: coefficient(1),
+        two_thirds: coefficient(2),
+    })
+}
+
+/// Distribution of the commission percentages of the validators joining during genesis
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CommissionStats {
+    pub min: u64,
+    /// The lower of the middle two for an even number of validators
+    pub median: u64,
+    pub max: u64,
+    /// Rounded down
+    pub mean: u64,
+}
+
+/// Computes the commission stats of the validators joining during genesis
+///
+/// Returns `None` if no validator joins during genesis.
+pub fn commission_stats<'a>(
+    validators: impl IntoIterator<Item = &'a ValidatorSummary>,
+) -> Option<CommissionStats> {
+    let mut percentages: Vec<u64> = validators
+        .into_iter()
+        .filter(|validator| validator.join_during_genesis)
+        .map(|validator| validator.commission_percentage)
+        .collect();
+    if percentages.is_empty() {
+        return None;
+    }
+
+    percentages.sort_unstable();
+    let total: u128 = percentages
+        .iter()
+        .map(|percentage| *percentage as u128)
+        .sum();
+    Some(CommissionStats {
+        min: percentages[0],
+        median: percentages[(percentages.len() - 1) / 2],
+        max: percentages[percentages.len() - 1],
+        mean: (total / percentages.len() as u128) as u64,
+    })
+}
+
+/// Summarizes validators, leaving out any validator addresses that aren't to be published
+pub fn summarize_validators<'a>(
+    validators: impl IntoIterator<Item = &'a ValidatorConfiguration>,
+) -> CliTypedResult<Vec<ValidatorSummary>> {
+    let mut summaries = Vec::new();
+    for validator in validators {
+        let validator_network_address = match (
+            validator.publish_validator_address,
+            validator.validator_host.as_ref(),
+            validator.validator_network_public_key,
+        ) {
+            (true, Some(host), Some(key)) => Some(host.as_network_address(key)?),
+            _ => None,
+        };
+        let full_node_network_address = match (
+            validator.full_node_host.as_ref(),
+            validator.full_node_network_public_key,
+        ) {
+            (Some(host), Some(key)) => Some(host.as_network_address(key)?),
+            _ => None,
+        };
+
+        summaries.push(ValidatorSummary {
+            owner_account_address: validator.owner_account_address,
+            operator_account_address: validator.operator_account_address,
+            stake_amount: validator.stake_amount,
+            commission_percentage: validator.commission_percentage,
+            join_during_genesis: validator.join_during_genesis,
+            employee_pool: None,
+            validator_network_address,
+            full_node_network_address,
+        });
+    }
+    Ok(summaries)
+}
+
+/// Every input file that genesis was built from, for comparing builds between coordinators
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct GenesisManifest {
+    /// Repository the inputs were read from e.g. `github:owner/repository@main`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Commit at the head of the branch, not known for a local repository
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Hex encoded SHA-256 hash of each file, keyed by its path in the repository
+    pub files: BTreeMap<String, String>,
+}
+
+impl GenesisManifest {
+    pub fn new(
+        source: Option<GitSource>,
+        commit: Option<String>,
+        reads: BTreeMap<PathBuf, String>,
+    ) -> GenesisManifest {
+        GenesisManifest {
+            source: source.map(|source| source.to_string()),
+            commit,
+            files: reads
+                .into_iter()
+                .map(|(path, hash)| (path.display().to_string(), hash))
+                .collect(),
+        }
+    }
+}
+
+/// Seed peers for the network at genesis, built only from published addresses
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct GenesisSeeds {
+    /// Seeds for the validator network, keyed by the validator's owner account
+    pub validator_network: BTreeMap<PeerId, Peer>,
+    /// Seeds for the public full node network, keyed by the full node's network identity
+    pub full_node_network: BTreeMap<PeerId, Peer>,
+}
+
+impl GenesisSeeds {
+    /// Builds seeds from the validators joining during genesis
+    pub fn from_summaries(validators: &[ValidatorSummary]) -> GenesisSeeds {
+        let mut seeds = GenesisSeeds::default();
+        for validator in validators.iter().filter(|v| v.join_during_genesis) {
+            if let Some(ref address) = validator.validator_network_address {
+                seeds.validator_network.insert(
+                    validator.owner_account_address,
+                    Peer::from_addrs(PeerRole::Validator, vec![address.clone()]),
+                );
+            }
+            if let Some(ref address) = validator.full_node_network_address {
+                if let Some(key) = address.find_noise_proto() {
+                    seeds.full_node_network.insert(
+                        from_identity_public_key(key),
+                        Peer::from_addrs(PeerRole::Upstream, vec![address.clone()]),
+                    );
+                }
+            }
+        }
+        seeds
+    }
+}
+
+/// Seed peers in the shape of a node config, to merge into the configs of the network's nodes
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NodeSeedsConfig {
+    pub validator_network: NetworkSeeds,
+    pub full_node_networks: Vec<NetworkSeeds>,
+}
+
+/// Seed peers of one network in a node config
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NetworkSeeds {
+    pub network_id: NetworkId,
+    pub seeds: BTreeMap<PeerId, Peer>,
+}
+
+impl From<GenesisSeeds> for NodeSeedsConfig {
+    fn from(seeds: GenesisSeeds) -> Self {
+        NodeSeedsConfig {
+            validator_network: NetworkSeeds {
+                network_id: NetworkId::Validator,
+                seeds: seeds.validator_network,
+            },
+            full_node_networks: vec![NetworkSeeds {
+                network_id: NetworkId::Public,
+                seeds: seeds.full_node_network,
+            }],
+        }
+    }
+}
+
+/// A file of initial balances, so reviewers can confirm which files went into genesis
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BalancesFileSummary {
+    /// Path of the file in the repository
+    pub file: String,
+    /// SHA-256 hex of the file's contents, the same hash as in the manifest
+    pub hash: String,
+    pub num_accounts: usize,
+    pub total_balance: u64,
+}
+
+/// Accounts with an initial balance that nothing else in genesis refers to
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct UnreferencedBalances {
+    /// Balance of each unreferenced account
+    pub accounts: BTreeMap<AccountAddress, u64>,
+    /// Combined balance of all unreferenced accounts, which can be more than fits in a `u64`
+    pub total_balance: u128,
+}
+
+/// Finds all accounts with a balance that are not an owner, operator, or voter of a validator,
+/// not in an employee pool, and not the beneficiary resetter of an employee pool
+pub fn find_unreferenced_balances(
+    initialized_accounts: &BTreeMap<AccountAddress, u64>,
+    validators: &[ValidatorConfiguration],
+    employee_pools: &[EmployeePool],
+) -> UnreferencedBalances {
+    let mut referenced = BTreeSet::new();
+    for validator in validators {
+        referenced.insert(validator.owner_account_address);
+        referenced.insert(validator.operator_account_address);
+        referenced.insert(validator.voter_account_address);
+    }
+    for pool in employee_pools {
+        referenced.extend(pool.accounts.iter().copied());
+        referenced.insert(pool.beneficiary_resetter);
+        referenced.insert(pool.validator.validator.owner_address);
+        referenced.insert(pool.validator.validator.operator_address);
+        referenced.insert(pool.validator.validator.voter_address);
+    }
+
+    let accounts: BTreeMap<AccountAddress, u64> = initialized_accounts
+        .iter()
+        .filter(|(account, _)| !referenced.contains(account))
+        .map(|(account, balance)| (*account, *balance))
+        .collect();
+    let total_balance = accounts.values().map(|balance| *balance as u128).sum();
+
+    UnreferencedBalances {
+        accounts,
+        total_balance,
+    }
+}
+
+/// Aggregate numbers describing a candidate genesis, for reviewing it at a glance
+///
+/// Numbers that can't be recovered from where the stats were computed from are left out, e.g. a
+/// genesis blob only holds the validators joining during genesis.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct GenesisStats {
+    pub num_validators: usize,
+    pub num_joining: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_deferred: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_supply: Option<u64>,
+    /// Combined stake of the validators joining during genesis
+    pub total_stake: u64,
+    /// Total stake in basis points of the total supply, as checked during validation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub staked_supply_bps: Option<u64>,
+    /// Median stake of the validators joining during genesis, the lower of the middle two
+    pub median_stake: u64,
+    /// Mean stake of the validators joining during genesis, rounded down
+    pub mean_stake: u64,
+    /// Number of validators with each commission percentage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commission_percentages: Option<BTreeMap<u64, usize>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commission_stats: Option<CommissionStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_operators: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nakamoto_coefficients: Option<NakamotoCoefficients>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_employee_pools: Option<usize>,
+    /// Combined stake of all employee pools, whether or not they join during genesis
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub employee_pools_stake: Option<u64>,
+    /// Number of network addresses by the top level domain of their host, `ip` for IP addresses
+    pub host_tlds: BTreeMap<String, usize>,
+}
+
+impl GenesisStats {
+    /// Computes stats from the summary built while validating the repository
+    pub fn from_summary(summary: &GenesisSummary) -> GenesisStats {
+        let validators = &summary.validators;
+        let joining_stakes: Vec<u64> = validators
+            .iter()
+            .filter(|validator| validator.join_during_genesis)
+            .map(|validator| validator.stake_amount)
+            .collect();
+
+        let mut commission_percentages = BTreeMap::new();
+        for validator in validators {
+            *commission_percentages
+                .entry(validator.commission_percentage)
+                .or_insert(0) += 1;
+        }
+        let operators: BTreeSet<_> = validators
+            .iter()
+            .map(|validator| validator.operator_account_address)
+            .collect();
+        let employee_pools: Vec<_> = validators
+            .iter()
+            .filter(|validator| validator.employee_pool.is_some())
+            .collect();
+        let addresses = validators.iter().flat_map(|validator| {
+            validator
+                .validator_network_address
+                .iter()
+                .chain(validator.full_node_network_address.iter())
+        });
+
+        let mut stats = GenesisStats::from_stakes(&joining_stakes, addresses);
+        stats.num_validators = validators.len();
+        stats.num_deferred = Some(validators.len() - joining_stakes.len());
+        stats.total_supply = summary.total_supply;
+        stats.staked_supply_bps = summary.staked_supply_bps;
+        stats.commission_percentages = Some(commission_percentages);
+        stats.commission_stats = summary.commission_stats;
+        stats.num_operators = Some(operators.len());
+        stats.nakamoto_coefficients = summary.nakamoto_coefficients;
+        if summary.total_supply.is_some() {
+            stats.num_employee_pools = Some(employee_pools.len());
+            stats.employee_pools_stake = Some(
+                employee_pools
+                    .iter()
+                    .map(|validator| validator.stake_amount)
+                    .sum(),
+            );
+        }
+        stats
+    }
+
+    /// Computes stats from the validator set written by a genesis transaction
+    pub fn from_genesis(genesis: &Transaction) -> CliTypedResult<GenesisStats> {
+        let validator_set = genesis_validator_set(genesis)?;
+
+        let validators: Vec<_> = validator_set
+            .active_validators
+            .iter()
+            .chain(validator_set.pending_active.iter())
+            .collect();
+        let stakes: Vec<u64> = validators
+            .iter()
+            .map(|validator| validator.consensus_voting_power())
+            .collect();
+        let mut addresses = Vec::new();
+        for validator in &validators {
+            addresses.extend(validator.config().validator_network_addresses()?);
+            addresses.extend(validator.config().fullnode_network_addresses()?);
+        }
+
+        let mut stats = GenesisStats::from_stakes(&stakes, addresses.iter());
+        stats.num_validators = validators.len();
+        Ok(stats)
+    }
+
+    fn from_stakes<'a>(
+        stakes: &[u64],
+        addresses: impl IntoIterator<Item = &'a NetworkAddress>,
+    ) -> GenesisStats {
+        let total_stake: u128 = stakes.iter().map(|stake| *stake as u128).sum();
+        let mut sorted_stakes = stakes.to_vec();
+        sorted_stakes.sort_unstable();
+
+        let mut host_tlds = BTreeMap::new();
+        for address in addresses {
+            if let Some(tld) = host_tld(address) {
+                *host_tlds.entry(tld).or_insert(0) += 1;
+            }
+        }
+
+        GenesisStats {
+            num_joining: stakes.len(),
+            total_stake: total_stake as u64,
+            median_stake: if sorted_stakes.is_empty() {
+                0
+            } else {
+                sorted_stakes[(sorted_stakes.len() - 1) / 2]
+            },
+            mean_stake: if stakes.is_empty() {
+                0
+            } else {
+                (total_stake / stakes.len() as u128) as u64
+            },
+            host_tlds,
+            ..Default::default()
+        }
+    }
+
+    /// Formats the stats as a two column table
+    pub fn to_table(&self) -> String {
+        let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        let counts = |counts: &BTreeMap<String, usize>| {
+            counts
+                .iter()
+                .map(|(key, count)| format!("{}: {}", key, count))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let rows = vec![
+            ("Validators", self.num_validators.to_string()),
+            ("Joining during genesis", self.num_joining.to_string()),
+            (
+                "Deferred",
+                optional(self.num_deferred.map(|num| num.to_string())),
+            ),
+            (
+                "Total supply",
+                optional(self.total_supply.map(|supply| supply.to_string())),
+            ),
+            ("Total stake", self.total_stake.to_string()),
+            (
+                "Staked supply",
+                optional(
+                    self.staked_supply_bps
+                        .map(|bps| format!("{}.{:02}%", bps / 100, bps % 100)),
+                ),
+            ),
+            ("Median stake", self.median_stake.to_string()),
+            ("Mean stake", self.mean_stake.to_string()),
+            (
+                "Commission percentages",
+                optional(self.commission_percentages.as_ref().map(|percentages| {
+                    counts(
+                        &percentages
+                            .iter()
+                            .map(|(percentage, count)| (format!("{}%", percentage), *count))
+                            .collect(),
+                    )
+                })),
+            ),
+            (
+                "Commission (min/median/max/mean)",
+                optional(self.commission_stats.map(|stats| {
+                    format!(
+                        "{}% / {}% / {}% / {}%",
+                        stats.min, stats.median, stats.max, stats.mean
+                    )
+                })),
+            ),
+            (
+                "Operators",
+                optional(self.num_operators.map(|num| num.to_string())),
+            ),
+            (
+                "Nakamoto coefficient (1/3)",
+                optional(
+                    self.nakamoto_coefficients
+                        .map(|coefficients| coefficients.third.to_string()),
+                ),
+            ),
+            (
+                "Nakamoto coefficient (2/3)",
+                optional(
+                    self.nakamoto_coefficients
+                        .map(|coefficients| coefficients.two_thirds.to_string()),
+                ),
+            ),
+            (
+                "Employee pools",
+                optional(self.num_employee_pools.map(|num| num.to_string())),
+            ),
+            (
+                "Employee pools stake",
+                optional(self.employee_pools_stake.map(|stake| stake.to_string())),
+            ),
+            ("Host TLDs", counts(&self.host_tlds)),
+        ];
+
+        let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        rows.iter()
+            .map(|(name, value)| format!("{:width$}  {}", name, value, width = width))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Top level domain of the host of a network address, or `ip` if the host is an IP address
+fn host_tld(address: &NetworkAddress) -> Option<String> {
+    match address.as_slice().first()? {
+        Protocol::Ip4(_) | Protocol::Ip6(_) => Some("ip".to_string()),
+        Protocol::Dns(name) | Protocol::Dns4(name) | Protocol::Dns6(name) => name
+            .as_ref()
+            .trim_end_matches('.')
+            .rsplit('.')
+            .next()
+            .map(str::to_lowercase),
+        _ => None,
+    }
+}
@@ -1,38 +1,80 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::types::OptionalPoolAddressArgs;
+use crate::common::types::{CliError, OptionalPoolAddressArgs};
 use crate::common::utils::read_from_file;
-use crate::genesis::git::{from_yaml, BALANCES_FILE, EMPLOYEE_VESTING_ACCOUNTS_FILE};
-use crate::genesis::git::{FRAMEWORK_NAME, OWNER_FILE};
-use crate::genesis::keys::{GenerateLayoutTemplate, PUBLIC_KEYS_FILE};
+use crate::genesis::git::{
+    file_hash, from_yaml, to_yaml, BALANCES_DIR, BALANCES_FILE, EMPLOYEE_VESTING_ACCOUNTS_FILE,
+};
+use crate::genesis::git::{
+    FRAMEWORK_NAME, LAYOUT_FILE, OPERATOR_FILE, OWNER_FILE, OWNER_JSON_FILE,
+};
+use crate::genesis::keys::{
+    prompt_layout, role_private_key_file, role_public_key_file, GenerateLayoutTemplate,
+    CONSENSUS_ROLE, FULL_NODE_NETWORK_ROLE, OWNER_ROLE, PRIVATE_KEYS_FILE, PUBLIC_KEYS_FILE,
+    VALIDATOR_NETWORK_ROLE, VOTER_ROLE,
+};
+use crate::genesis::summary::{
+    commission_stats, genesis_framework_config, genesis_total_supply, nakamoto_coefficients,
+    validator_set_hash, CommissionStats, GenesisJson, GenesisManifest, GenesisSeeds, GenesisStats,
+    GenesisSummary, NakamotoCoefficients, NodeSeedsConfig, ValidatorSummary,
+    FRAMEWORK_PACKAGE_NAME, SEEDS_FILE, SUMMARY_FILE,
+};
+use crate::genesis::{
+    csv_field, fetch_genesis_info, fetch_mainnet_genesis_info, get_validator_configs,
+    get_validator_configs_partial, load_and_validate_layout, mb_to_bytes, parse_balances_csv,
+    resolve_chain_id, verify_framework_config, verify_genesis_contents, write_genesis_outputs,
+    ExportNodeSeeds, ExportValidatorsCsv, FindEmployeeAccount, GenerateTestGenesis, GenesisOptions,
+    GenesisTool, GetValidatorConfig, Stats, StatsFormat, ValidateUser, WaypointEncoding,
+    DEFAULT_GENESIS_SIZE_BUDGET_MB,
+};
 use crate::{
     common::{
         types::{PromptOptions, RngArgs},
         utils::write_to_file,
     },
     genesis::{
-        git::{GitOptions, SetupGit},
-        keys::{GenerateKeys, SetValidatorConfiguration},
+        git::{
+            Client, GitChanges, GitOptions, GitSource, GithubRepo, GithubTokenSource, MergeRepos,
+            RateLimitedGithubClient, SetupGit, Storage,
+        },
+        keys::{GenerateBeneficiaryResetWriteSet, GenerateKeys, SetValidatorConfiguration},
         GenerateGenesis,
     },
     CliCommand,
 };
+use aptos_config::config::PeerRole;
+use aptos_config::network_id::NetworkId;
 use aptos_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
-    PrivateKey,
+    multi_ed25519::MultiEd25519PublicKey,
+    PrivateKey, ValidCryptoMaterial,
 };
 use aptos_genesis::config::{
-    AccountBalanceMap, EmployeePoolConfig, EmployeePoolMap, HostAndPort, Layout,
-    OwnerConfiguration, ValidatorConfiguration,
+    parse_amount, parse_number, AccountBalanceMap, AccountPublicKey, EmployeePoolConfig,
+    EmployeePoolMap, HostAndPort, Layout, OwnerConfiguration, StringOperatorConfiguration,
+    StringOwnerConfiguration, ValidatorConfiguration, MAX_EPOCH_DURATION_SECS,
+    MAX_RECURRING_LOCKUP_DURATION_SECS, MIN_EPOCH_DURATION_SECS, MIN_VOTING_DURATION_SECS,
+    OCTAS_PER_APT,
 };
-use aptos_genesis::keys::PublicIdentity;
+use aptos_genesis::keys::{PublicAccountKey, PublicConsensusKey, PublicIdentity, PublicNetworkKey};
+use aptos_github_client::Client as GithubClient;
 use aptos_keygen::KeyGen;
 use aptos_temppath::TempPath;
-use aptos_types::account_address::AccountAddress;
-use aptos_types::chain_id::ChainId;
+use aptos_types::account_address::{
+    create_vesting_contract_address, default_stake_pool_address, AccountAddress,
+};
+use aptos_types::chain_id::{ChainId, NamedChain};
+use aptos_types::transaction::{authenticator::AuthenticationKey, Transaction};
+use httpmock::{
+    Method::{GET, PATCH, POST, PUT},
+    MockServer,
+};
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
+    net::{IpAddr, ToSocketAddrs},
     path::{Path, PathBuf},
     str::FromStr,
 };
@@ -64,50 +106,9 @@ async fn test_genesis_e2e_flow() {
 async fn test_mainnet_genesis_e2e_flow() {
     let dir = TempPath::new();
     dir.create_as_dir().unwrap();
-    let git_options = create_users(2, &dir, &mut vec![10, 0]).await;
-
     let account_1 = AccountAddress::from_hex_literal("0x101").unwrap();
     let account_2 = AccountAddress::from_hex_literal("0x102").unwrap();
-    let employee_1 = AccountAddress::from_hex_literal("0x201").unwrap();
-    let employee_2 = AccountAddress::from_hex_literal("0x202").unwrap();
-    let employee_3 = AccountAddress::from_hex_literal("0x203").unwrap();
-    let employee_4 = AccountAddress::from_hex_literal("0x204").unwrap();
-    let admin_dir = generate_keys(dir.path(), 2).await;
-    let admin_identity: PublicIdentity = from_yaml(
-        &String::from_utf8(read_from_file(admin_dir.join(PUBLIC_KEYS_FILE).as_path()).unwrap())
-            .unwrap(),
-    )
-    .unwrap();
-
-    // Create initial balances and employee vesting account files.
-    let git_dir = git_options.local_repository_dir.as_ref().unwrap().as_path();
-
-    // Load the standalone staking pools
-    let staker_0 = get_owner_address(git_options.clone(), 0);
-    let staker_1 = get_owner_address(git_options.clone(), 1);
-
-    create_account_balances_file(
-        PathBuf::from(git_dir),
-        vec![
-            staker_0,
-            staker_1,
-            account_1,
-            account_2,
-            employee_1,
-            employee_2,
-            employee_3,
-            employee_4,
-            admin_identity.account_address,
-        ],
-    )
-    .await;
-    create_employee_vesting_accounts_file(
-        PathBuf::from(git_dir),
-        admin_identity,
-        &vec![vec![employee_1, employee_2], vec![employee_3, employee_4]],
-        &[true, false],
-    )
-    .await;
+    let git_options = setup_mainnet_git_dir(&dir, vec![account_1, account_2]).await;
 
     // Now generate genesis
     let output_dir = TempPath::new();
@@ -122,6 +123,3043 @@ async fn test_mainnet_genesis_e2e_flow() {
     assert!(genesis_file.exists());
 }
 
+#[tokio::test]
+async fn test_emit_json_genesis() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(1, &dir, &mut vec![]).await;
+
+    let output_dir = TempPath::new();
+    output_dir.create_as_dir().unwrap();
+    let output_dir = PathBuf::from(output_dir.path());
+    let files = GenerateGenesis {
+        prompt_options: PromptOptions::yes(),
+        git_options,
+        output_dir: Some(output_dir.clone()),
+        mainnet: false,
+        print_validator_set_hash: false,
+        genesis_size_budget_mb: DEFAULT_GENESIS_SIZE_BUDGET_MB,
+        max_genesis_size_mb: None,
+        emit_json_genesis: true,
+        waypoint_encoding: WaypointEncoding::Plain,
+        manifest: None,
+        verify_output: false,
+        deep_verify: false,
+        genesis_options: GenesisOptions::default(),
+    }
+    .execute()
+    .await
+    .unwrap();
+    let genesis_json_file = output_dir.join("genesis.json");
+    assert!(files.contains(&genesis_json_file));
+
+    // The framework should only show up as its hash
+    let genesis_json: GenesisJson =
+        serde_json::from_slice(&read_from_file(genesis_json_file.as_path()).unwrap()).unwrap();
+    assert!(genesis_json.framework.num_writes > 0);
+    assert!(genesis_json
+        .writes
+        .iter()
+        .all(|write| !write.key.contains("/code/")));
+    assert!(genesis_json
+        .writes
+        .iter()
+        .any(|write| write.key == "0x1/resource/0x1::stake::ValidatorSet"));
+    assert!(!genesis_json.events.is_empty());
+}
+
+#[tokio::test]
+async fn test_genesis_stats() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let stats = |genesis_file: Option<PathBuf>, format: StatsFormat| Stats {
+        mainnet: true,
+        genesis_file,
+        format,
+        genesis_options: GenesisOptions::default(),
+        git_options: git_options.clone(),
+    };
+
+    // Two standalone validators and two employee pools, of which the second doesn't join
+    let output = stats(None, StatsFormat::Json).execute().await.unwrap();
+    let repo_stats: GenesisStats = serde_json::from_str(&output).unwrap();
+    let (_, summary) =
+        fetch_mainnet_genesis_info(git_options.clone(), &GenesisOptions::default()).unwrap();
+    assert_eq!(4, repo_stats.num_validators);
+    assert_eq!(3, repo_stats.num_joining);
+    assert_eq!(Some(1), repo_stats.num_deferred);
+    assert_eq!(Some(7 * INITIAL_BALANCE), repo_stats.total_supply);
+    assert_eq!(4 * INITIAL_BALANCE, repo_stats.total_stake);
+    assert_eq!(summary.staked_supply_bps, repo_stats.staked_supply_bps);
+    assert_eq!(INITIAL_BALANCE, repo_stats.median_stake);
+    assert_eq!(4 * INITIAL_BALANCE / 3, repo_stats.mean_stake);
+    assert_eq!(
+        Some(BTreeMap::from([(0, 3), (10, 1)])),
+        repo_stats.commission_percentages
+    );
+    // Only the joining validators' commission counts
+    assert_eq!(
+        Some(CommissionStats {
+            min: 0,
+            median: 0,
+            max: 10,
+            mean: 3
+        }),
+        repo_stats.commission_stats
+    );
+    assert_eq!(summary.commission_stats, repo_stats.commission_stats);
+    assert_eq!(Some(3), repo_stats.num_operators);
+    assert_eq!(
+        summary.nakamoto_coefficients,
+        repo_stats.nakamoto_coefficients
+    );
+    assert_eq!(Some(2), repo_stats.num_employee_pools);
+    assert_eq!(Some(4 * INITIAL_BALANCE), repo_stats.employee_pools_stake);
+    assert_eq!(
+        BTreeMap::from([("localhost".to_string(), 4)]),
+        repo_stats.host_tlds
+    );
+
+    let table = stats(None, StatsFormat::Table).execute().await.unwrap();
+    assert!(table.contains("Staked supply"), "{}", table);
+    assert!(table.contains("0% / 0% / 10% / 3%"), "{}", table);
+
+    // The validator set in the genesis blob has the same stake as the repository
+    let output_dir = TempPath::new();
+    output_dir.create_as_dir().unwrap();
+    let output_dir = PathBuf::from(output_dir.path());
+    generate_genesis(git_options.clone(), output_dir.clone(), true).await;
+    let output = stats(Some(output_dir.join("genesis.blob")), StatsFormat::Json)
+        .execute()
+        .await
+        .unwrap();
+    let blob_stats: GenesisStats = serde_json::from_str(&output).unwrap();
+    assert_eq!(repo_stats.num_joining, blob_stats.num_validators);
+    assert_eq!(repo_stats.num_joining, blob_stats.num_joining);
+    assert_eq!(repo_stats.total_stake, blob_stats.total_stake);
+    assert_eq!(repo_stats.median_stake, blob_stats.median_stake);
+    assert_eq!(repo_stats.mean_stake, blob_stats.mean_stake);
+    assert_eq!(None, blob_stats.num_deferred);
+
+    // The command prints the stats as is, but wraps errors like every other command
+    let table = GenesisTool::Stats(stats(None, StatsFormat::Table))
+        .execute()
+        .await
+        .unwrap();
+    assert!(table.contains("Staked supply"), "{}", table);
+    let err = GenesisTool::Stats(stats(
+        Some(output_dir.join("missing.blob")),
+        StatsFormat::Table,
+    ))
+    .execute()
+    .await
+    .unwrap_err();
+    let err: serde_json::Value = serde_json::from_str(&err).unwrap();
+    assert!(err.get("Error").is_some(), "{}", err);
+}
+
+#[test]
+fn test_mb_to_bytes() {
+    assert_eq!(
+        2 * 1024 * 1024,
+        mb_to_bytes("--max-genesis-size-mb", 2).unwrap()
+    );
+
+    // Sizes that don't fit in bytes are rejected rather than wrapping
+    let err = mb_to_bytes("--max-genesis-size-mb", u64::MAX).unwrap_err();
+    assert!(matches!(err, CliError::CommandArgumentError(_)), "{}", err);
+    assert!(err.to_string().contains("--max-genesis-size-mb"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_genesis_size_budget() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(1, &dir, &mut vec![]).await;
+
+    // Inflate the framework with 2 MB of module source
+    let mut framework = cached_packages::head_release_bundle().clone();
+    framework.packages[0].package_metadata_mut().modules[0]
+        .source
+        .extend(vec![0u8; 2 * 1024 * 1024]);
+    framework
+        .write(
+            git_options
+                .local_repository_dir
+                .as_ref()
+                .unwrap()
+                .join(FRAMEWORK_NAME),
+        )
+        .unwrap();
+
+    // Over the budget only warns
+    let output_dir = TempPath::new();
+    output_dir.create_as_dir().unwrap();
+    let output_dir = PathBuf::from(output_dir.path());
+    GenerateGenesis {
+        prompt_options: PromptOptions::yes(),
+        git_options: git_options.clone(),
+        output_dir: Some(output_dir.clone()),
+        mainnet: false,
+        print_validator_set_hash: false,
+        genesis_size_budget_mb: 1,
+        max_genesis_size_mb: None,
+        emit_json_genesis: false,
+        waypoint_encoding: WaypointEncoding::Plain,
+        manifest: None,
+        verify_output: false,
+        deep_verify: false,
+        genesis_options: GenesisOptions::default(),
+    }
+    .execute()
+    .await
+    .unwrap();
+    let summary: GenesisSummary = read_yaml_file(output_dir.join(SUMMARY_FILE).as_path());
+    let size = summary.genesis_size.unwrap();
+    assert!(size.over_budget);
+    assert_eq!(1024 * 1024, size.budget_bytes);
+    assert!(size.framework_bytes > 2 * 1024 * 1024);
+    assert!(size.framework_bps > 5_000);
+    assert!(size.validator_set_bytes > 0);
+    assert!(size.resources_bytes > 0);
+    assert!(
+        size.framework_bytes + size.validator_set_bytes + size.resources_bytes <= size.total_bytes
+    );
+
+    // Over the maximum fails without writing genesis
+    let output_dir = TempPath::new();
+    output_dir.create_as_dir().unwrap();
+    let output_dir = PathBuf::from(output_dir.path());
+    GenerateGenesis {
+        prompt_options: PromptOptions::yes(),
+        git_options,
+        output_dir: Some(output_dir.clone()),
+        mainnet: false,
+        print_validator_set_hash: false,
+        genesis_size_budget_mb: DEFAULT_GENESIS_SIZE_BUDGET_MB,
+        max_genesis_size_mb: Some(1),
+        emit_json_genesis: false,
+        waypoint_encoding: WaypointEncoding::Plain,
+        manifest: None,
+        verify_output: false,
+        deep_verify: false,
+        genesis_options: GenesisOptions::default(),
+    }
+    .execute()
+    .await
+    .unwrap_err();
+    assert!(!output_dir.join("genesis.blob").exists());
+}
+
+#[tokio::test]
+async fn test_genesis_manifest() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let generate = |git_options: GitOptions| async move {
+        let output_dir = TempPath::new();
+        output_dir.create_as_dir().unwrap();
+        let manifest_file = output_dir.path().join("manifest.json");
+        let files = GenerateGenesis {
+            prompt_options: PromptOptions::yes(),
+            git_options,
+            output_dir: Some(PathBuf::from(output_dir.path())),
+            mainnet: true,
+            print_validator_set_hash: false,
+            genesis_size_budget_mb: DEFAULT_GENESIS_SIZE_BUDGET_MB,
+            max_genesis_size_mb: None,
+            emit_json_genesis: false,
+            waypoint_encoding: WaypointEncoding::Plain,
+            manifest: Some(manifest_file.clone()),
+            verify_output: false,
+            deep_verify: false,
+            genesis_options: GenesisOptions::default(),
+        }
+        .execute()
+        .await
+        .unwrap();
+        assert!(files.contains(&manifest_file));
+        serde_json::from_slice::<GenesisManifest>(&read_from_file(&manifest_file).unwrap()).unwrap()
+    };
+
+    // Every input is listed with the hash of its contents
+    let git_dir = git_options.local_repository_dir.clone().unwrap();
+    let manifest = generate(git_options.clone()).await;
+    assert_eq!(Some(git_dir.display().to_string()), manifest.source);
+    assert_eq!(None, manifest.commit);
+    for file in [
+        LAYOUT_FILE,
+        BALANCES_FILE,
+        EMPLOYEE_VESTING_ACCOUNTS_FILE,
+        FRAMEWORK_NAME,
+        "user-0/owner.yaml",
+        "user-0/operator.yaml",
+        "user-1/owner.yaml",
+        "user-1/operator.yaml",
+    ] {
+        let contents = read_from_file(&git_dir.join(file)).unwrap();
+        assert_eq!(
+            Some(&hex::encode(Sha256::digest(&contents))),
+            manifest.files.get(file),
+            "{}",
+            file
+        );
+    }
+
+    // Identical inputs give identical manifests, and a changed input shows up
+    assert_eq!(manifest.files, generate(git_options.clone()).await.files);
+    let client = git_options.clone().get_client().unwrap();
+    let mut layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+    layout.min_nakamoto_coefficient_third = Some(0);
+    client.put(Path::new(LAYOUT_FILE), &layout).unwrap();
+    let changed = generate(git_options).await;
+    for (file, hash) in manifest.files {
+        assert_eq!(
+            file != LAYOUT_FILE,
+            changed.files[&file] == hash,
+            "{}",
+            file
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_export_validators_csv() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let client = git_options.clone().get_client().unwrap();
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+    let validators = get_validator_configs(&client, &layout, true, false).unwrap();
+    let output_file = dir.path().join("validators.csv");
+    ExportValidatorsCsv {
+        output_file: output_file.clone(),
+        mainnet: true,
+        genesis_options: GenesisOptions::default(),
+        prompt_options: PromptOptions::yes(),
+        git_options,
+    }
+    .execute()
+    .await
+    .unwrap();
+
+    // A header, then both users and both employee pools
+    let csv = String::from_utf8(read_from_file(output_file.as_path()).unwrap()).unwrap();
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(5, lines.len(), "{}", csv);
+    assert!(
+        lines[0].starts_with("name,owner_account_address,"),
+        "{}",
+        csv
+    );
+    let user_row: Vec<_> = lines[1].split(',').collect();
+    assert_eq!(12, user_row.len());
+    assert_eq!(format!("user {}", layout.users[0]), user_row[0]);
+    assert_eq!(
+        validators[0].owner_account_address.to_hex_literal(),
+        user_row[1]
+    );
+    assert_eq!(validators[0].stake_amount.to_string(), user_row[4]);
+    assert_eq!(validators[0].commission_percentage.to_string(), user_row[5]);
+    assert!(user_row[9].contains(".."), "{}", user_row[9]);
+    assert!(lines[3].starts_with("employee pool #0,"), "{}", csv);
+    assert!(lines[4].starts_with("employee pool #1,"), "{}", csv);
+
+    // Fields are only quoted when needed
+    assert_eq!("user-0", csv_field("user-0"));
+    assert_eq!("\"a, b\"", csv_field("a, b"));
+    assert_eq!("\"say \"\"hi\"\"\"", csv_field("say \"hi\""));
+}
+
+#[tokio::test]
+async fn test_export_node_seeds() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let output_file = dir.path().join("seeds.yaml");
+    ExportNodeSeeds {
+        output_file: output_file.clone(),
+        mainnet: true,
+        genesis_options: GenesisOptions::default(),
+        prompt_options: PromptOptions::yes(),
+        git_options: git_options.clone(),
+    }
+    .execute()
+    .await
+    .unwrap();
+
+    // Same seeds as genesis, in the node config's layout
+    let (_, summary) = fetch_mainnet_genesis_info(git_options, &GenesisOptions::default()).unwrap();
+    let expected = GenesisSeeds::from_summaries(&summary.validators);
+    let config: NodeSeedsConfig = read_yaml_file(output_file.as_path());
+    assert_eq!(NetworkId::Validator, config.validator_network.network_id);
+    assert!(!config.validator_network.seeds.is_empty());
+    assert_eq!(expected.validator_network, config.validator_network.seeds);
+    assert_eq!(1, config.full_node_networks.len());
+    assert_eq!(NetworkId::Public, config.full_node_networks[0].network_id);
+    assert_eq!(
+        expected.full_node_network,
+        config.full_node_networks[0].seeds
+    );
+
+    let yaml = String::from_utf8(read_from_file(output_file.as_path()).unwrap()).unwrap();
+    assert!(yaml.contains("network_id: public"), "{}", yaml);
+}
+
+#[tokio::test]
+async fn test_verify_genesis_output() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let output_dir = TempPath::new();
+    output_dir.create_as_dir().unwrap();
+    let output_dir = PathBuf::from(output_dir.path());
+    GenerateGenesis {
+        prompt_options: PromptOptions::yes(),
+        git_options,
+        output_dir: Some(output_dir.clone()),
+        mainnet: true,
+        print_validator_set_hash: false,
+        genesis_size_budget_mb: DEFAULT_GENESIS_SIZE_BUDGET_MB,
+        max_genesis_size_mb: None,
+        emit_json_genesis: false,
+        waypoint_encoding: WaypointEncoding::Plain,
+        manifest: None,
+        verify_output: true,
+        deep_verify: false,
+        // Progress notes are hidden, but the outputs are still written and verified
+        genesis_options: GenesisOptions {
+            quiet: true,
+            ..Default::default()
+        },
+    }
+    .execute()
+    .await
+    .unwrap();
+
+    // The blob has the supply from the layout
+    let genesis_file = output_dir.join("genesis.blob");
+    let summary: GenesisSummary = read_yaml_file(output_dir.join(SUMMARY_FILE).as_path());
+    let genesis: Transaction =
+        bcs::from_bytes(&read_from_file(genesis_file.as_path()).unwrap()).unwrap();
+    assert!(summary.total_supply.is_some());
+    assert_eq!(
+        summary.total_supply.map(u128::from),
+        genesis_total_supply(&genesis).unwrap()
+    );
+    verify_genesis_contents(genesis_file.as_path(), &summary, &GenesisOptions::default()).unwrap();
+
+    // A validator that's missing from the blob
+    let mut missing_validator = summary.clone();
+    missing_validator.validators.push(ValidatorSummary {
+        join_during_genesis: true,
+        ..summary.validators[0].clone()
+    });
+    let err = verify_genesis_contents(
+        genesis_file.as_path(),
+        &missing_validator,
+        &GenesisOptions::default(),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("joining during genesis"),
+        "{}",
+        err
+    );
+
+    // A supply that doesn't match the blob
+    let wrong_supply = GenesisSummary {
+        total_supply: summary.total_supply.map(|supply| supply + 1),
+        ..summary
+    };
+    let err = verify_genesis_contents(
+        genesis_file.as_path(),
+        &wrong_supply,
+        &GenesisOptions::default(),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("total supply"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_deep_verify_genesis() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let client = git_options.clone().get_client().unwrap();
+    let output_dir = TempPath::new();
+    output_dir.create_as_dir().unwrap();
+    let output_dir = PathBuf::from(output_dir.path());
+    GenerateGenesis {
+        prompt_options: PromptOptions::yes(),
+        git_options,
+        output_dir: Some(output_dir.clone()),
+        mainnet: true,
+        print_validator_set_hash: false,
+        genesis_size_budget_mb: DEFAULT_GENESIS_SIZE_BUDGET_MB,
+        max_genesis_size_mb: None,
+        emit_json_genesis: false,
+        waypoint_encoding: WaypointEncoding::Plain,
+        manifest: None,
+        verify_output: false,
+        deep_verify: true,
+        genesis_options: GenesisOptions::default(),
+    }
+    .execute()
+    .await
+    .unwrap();
+
+    // The framework config matches the layout
+    let genesis_file = output_dir.join("genesis.blob");
+    let summary: GenesisSummary = read_yaml_file(output_dir.join(SUMMARY_FILE).as_path());
+    let layout = load_and_validate_layout(&client, true).unwrap();
+    let genesis: Transaction =
+        bcs::from_bytes(&read_from_file(genesis_file.as_path()).unwrap()).unwrap();
+    let config = genesis_framework_config(&genesis).unwrap();
+    assert_eq!(layout.chain_id.id(), config.chain_id);
+    assert_eq!(layout.min_stake, config.min_stake);
+    assert_eq!(layout.min_voting_threshold, config.min_voting_threshold);
+    assert_eq!(layout.voting_duration_secs, config.voting_duration_secs);
+    assert!(config.allow_new_validators);
+
+    // Every mismatched value is reported
+    let wrong_layout = Layout {
+        max_stake: layout.max_stake + 1,
+        required_proposer_stake: layout.required_proposer_stake + 1,
+        ..layout.clone()
+    };
+    let err = verify_framework_config(
+        genesis_file.as_path(),
+        &wrong_layout,
+        &summary,
+        true,
+        &GenesisOptions::default(),
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(
+        err.contains(&format!(
+            "max_stake is {}, expected {}",
+            wrong_layout.max_stake - 1,
+            wrong_layout.max_stake
+        )),
+        "{}",
+        err
+    );
+    assert!(err.contains("required_proposer_stake is"), "{}", err);
+    assert!(!err.contains("min_stake"), "{}", err);
+
+    // The chain id genesis was built with is what's expected, not the layout's
+    let other_chain = GenesisSummary {
+        chain_id: summary.chain_id.map(|chain_id| chain_id + 1),
+        ..summary
+    };
+    let err = verify_framework_config(
+        genesis_file.as_path(),
+        &layout,
+        &other_chain,
+        true,
+        &GenesisOptions::default(),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("chain_id is"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_generate_test_genesis() {
+    let output_dir = TempPath::new();
+    output_dir.create_as_dir().unwrap();
+    let output_dir = PathBuf::from(output_dir.path());
+    GenerateTestGenesis {
+        output_dir: Some(output_dir.clone()),
+        num_validators: 3,
+        prompt_options: PromptOptions::yes(),
+        rng_args: RngArgs::from_seed([3; 32]),
+    }
+    .execute()
+    .await
+    .unwrap();
+
+    assert!(output_dir.join("genesis.blob").exists());
+    assert!(output_dir.join("waypoint.txt").exists());
+    assert!(output_dir.join("root-private-key.txt").exists());
+    let summary: GenesisSummary = read_yaml_file(output_dir.join(SUMMARY_FILE).as_path());
+    assert_eq!(3, summary.validators.len());
+
+    // Each validator has its own keys and port
+    let mut owners = HashSet::new();
+    for i in 0..3 {
+        let keys_dir = output_dir.join(format!("validator-{}", i));
+        assert!(keys_dir.join(PRIVATE_KEYS_FILE).exists());
+        let identity: PublicIdentity = read_yaml_file(keys_dir.join(PUBLIC_KEYS_FILE).as_path());
+        owners.insert(identity.account_address);
+    }
+    assert_eq!(3, owners.len());
+    let addresses: HashSet<_> = summary
+        .validators
+        .iter()
+        .map(|validator| validator.validator_network_address.clone())
+        .collect();
+    assert_eq!(3, addresses.len());
+
+    // Counts that would run out of ports are rejected up front
+    let err = GenerateTestGenesis {
+        output_dir: Some(output_dir),
+        num_validators: u16::MAX,
+        prompt_options: PromptOptions::yes(),
+        rng_args: RngArgs::from_seed([3; 32]),
+    }
+    .execute()
+    .await
+    .unwrap_err();
+    assert!(matches!(err, CliError::CommandArgumentError(_)), "{}", err);
+}
+
+#[tokio::test]
+async fn test_generate_keys_split_role_files() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let generate_keys =
+        |output_dir: PathBuf, operator_address: Option<AccountAddress>| GenerateKeys {
+            pool_address_args: OptionalPoolAddressArgs { pool_address: None },
+            rng_args: RngArgs::from_seed([7; 32]),
+            prompt_options: PromptOptions::yes(),
+            output_dir: Some(output_dir),
+            split_role_files: true,
+            operator_address,
+        };
+    let output_dir = dir.path().join("keys");
+    generate_keys(output_dir.clone(), None)
+        .execute()
+        .await
+        .unwrap();
+
+    // Each role's public keys should match the aggregate
+    let identity: PublicIdentity = read_yaml_file(&output_dir.join(PUBLIC_KEYS_FILE));
+    let owner_key: PublicAccountKey =
+        read_yaml_file(&output_dir.join(role_public_key_file(OWNER_ROLE)));
+    assert_eq!(identity.account_address, owner_key.account_address);
+    assert_eq!(identity.account_public_key, owner_key.account_public_key);
+
+    // Except the voter, which gets its own account key
+    let voter_key: PublicAccountKey =
+        read_yaml_file(&output_dir.join(role_public_key_file(VOTER_ROLE)));
+    assert_ne!(identity.account_address, voter_key.account_address);
+    assert_ne!(identity.account_public_key, voter_key.account_public_key);
+    assert_ne!(
+        std::fs::read_to_string(output_dir.join(role_private_key_file(OWNER_ROLE))).unwrap(),
+        std::fs::read_to_string(output_dir.join(role_private_key_file(VOTER_ROLE))).unwrap()
+    );
+
+    // Both can be used as a public identity file
+    for (role, address) in [
+        (OWNER_ROLE, owner_key.account_address),
+        (VOTER_ROLE, voter_key.account_address),
+    ] {
+        let role_identity: PublicIdentity =
+            read_yaml_file(&output_dir.join(role_public_key_file(role)));
+        assert_eq!(address, role_identity.account_address);
+        assert!(role_identity.consensus_public_key.is_none());
+    }
+    let consensus_key: PublicConsensusKey =
+        read_yaml_file(&output_dir.join(role_public_key_file(CONSENSUS_ROLE)));
+    assert_eq!(
+        identity.consensus_public_key.unwrap(),
+        consensus_key.consensus_public_key
+    );
+    assert_eq!(
+        identity.consensus_proof_of_possession.unwrap().to_bytes(),
+        consensus_key.consensus_proof_of_possession.to_bytes()
+    );
+    let validator_network_key: PublicNetworkKey =
+        read_yaml_file(&output_dir.join(role_public_key_file(VALIDATOR_NETWORK_ROLE)));
+    assert_eq!(
+        identity.validator_network_public_key.unwrap(),
+        validator_network_key.network_public_key
+    );
+    let full_node_network_key: PublicNetworkKey =
+        read_yaml_file(&output_dir.join(role_public_key_file(FULL_NODE_NETWORK_ROLE)));
+    assert_eq!(
+        identity.full_node_network_public_key.unwrap(),
+        full_node_network_key.network_public_key
+    );
+
+    // The pool address is derived from the owner and operator
+    let owner = identity.account_address;
+    let operator = AccountAddress::from_hex_literal("0x1234").unwrap();
+    assert_eq!(
+        default_stake_pool_address(owner, owner),
+        generate_keys(output_dir.clone(), None).pool_address(owner)
+    );
+    assert_eq!(
+        default_stake_pool_address(owner, operator),
+        generate_keys(output_dir.clone(), Some(operator)).pool_address(owner)
+    );
+
+    // And is stable across runs with the same keys
+    let other_output_dir = dir.path().join("other-keys");
+    generate_keys(other_output_dir.clone(), None)
+        .execute()
+        .await
+        .unwrap();
+    let other_identity: PublicIdentity = read_yaml_file(&other_output_dir.join(PUBLIC_KEYS_FILE));
+    assert_eq!(
+        generate_keys(output_dir, None).pool_address(owner),
+        generate_keys(other_output_dir, None).pool_address(other_identity.account_address)
+    );
+}
+
+#[tokio::test]
+async fn test_validator_set_hash() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(2, &dir, &mut vec![]).await;
+
+    // The same inputs must give the same hash
+    let (_, summary) = fetch_genesis_info(git_options.clone(), &GenesisOptions::default()).unwrap();
+    let (_, other_summary) =
+        fetch_genesis_info(git_options.clone(), &GenesisOptions::default()).unwrap();
+    let hash = summary.validator_set_hash.unwrap();
+    assert_eq!(hash, other_summary.validator_set_hash.unwrap());
+
+    // Order of the validators doesn't matter, but their contents do
+    let client = git_options.get_client().unwrap();
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+    let mut validators = get_validator_configs(&client, &layout, false, false).unwrap();
+    validators.reverse();
+    assert_eq!(hash, validator_set_hash(&validators).unwrap());
+    validators[0].stake_amount += 1;
+    assert_ne!(hash, validator_set_hash(&validators).unwrap());
+    validators[0].stake_amount -= 1;
+
+    // Leaving out different optional fields must not give the same hash
+    let mut without_host = validators.clone();
+    without_host[0].validator_host = None;
+    let mut without_key = validators.clone();
+    without_key[0].validator_network_public_key = None;
+    let without_host = validator_set_hash(&without_host).unwrap();
+    let without_key = validator_set_hash(&without_key).unwrap();
+    assert_ne!(hash, without_host);
+    assert_ne!(hash, without_key);
+    assert_ne!(without_host, without_key);
+}
+
+#[tokio::test]
+async fn test_expected_framework_version() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(1, &dir, &mut vec![]).await;
+
+    // The framework's version should be in the summary
+    let (_, summary) = fetch_genesis_info(git_options.clone(), &GenesisOptions::default()).unwrap();
+    let version = summary
+        .framework
+        .iter()
+        .find(|package| package.name == FRAMEWORK_PACKAGE_NAME)
+        .unwrap()
+        .version
+        .clone()
+        .unwrap();
+
+    let expected = GenesisOptions {
+        expected_framework_version: Some(version),
+        ..Default::default()
+    };
+    fetch_genesis_info(git_options.clone(), &expected).unwrap();
+    let stale = GenesisOptions {
+        expected_framework_version: Some("0.0.0-stale".to_string()),
+        ..Default::default()
+    };
+    assert!(fetch_genesis_info(git_options, &stale).is_err());
+}
+
+#[tokio::test]
+async fn test_genesis_waypoint_mismatch() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(1, &dir, &mut vec![]).await;
+    let (mut genesis_info, _) =
+        fetch_genesis_info(git_options.clone(), &GenesisOptions::default()).unwrap();
+    let waypoint = genesis_info.generate_waypoint().unwrap();
+    let genesis_bytes = bcs::to_bytes(genesis_info.get_genesis()).unwrap();
+
+    // A valid genesis for another chain, which only differs from the first in its chain id
+    let other_chain = GenesisOptions {
+        chain_id: Some(ChainId::new(42)),
+        ..Default::default()
+    };
+    let (mut other_genesis_info, _) = fetch_genesis_info(git_options, &other_chain).unwrap();
+    let other_genesis_bytes = bcs::to_bytes(other_genesis_info.get_genesis()).unwrap();
+    assert_ne!(waypoint, other_genesis_info.generate_waypoint().unwrap());
+
+    let output_dir = TempPath::new();
+    output_dir.create_as_dir().unwrap();
+    let genesis_file = output_dir.path().join("genesis.blob");
+    let waypoint_file = output_dir.path().join("waypoint.txt");
+
+    // Matching outputs are kept
+    write_genesis_outputs(
+        &genesis_file,
+        &waypoint_file,
+        &genesis_bytes,
+        waypoint,
+        WaypointEncoding::Plain,
+    )
+    .unwrap();
+    assert!(genesis_file.exists());
+    assert!(waypoint_file.exists());
+
+    // A genesis that doesn't match the waypoint is caught, and the outputs removed
+    let err = write_genesis_outputs(
+        &genesis_file,
+        &waypoint_file,
+        &other_genesis_bytes,
+        waypoint,
+        WaypointEncoding::Plain,
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(
+        err.contains(&format!("Waypoint {} in", waypoint)),
+        "{}",
+        err
+    );
+    assert!(err.contains("does not match waypoint"), "{}", err);
+    assert!(!genesis_file.exists());
+    assert!(!waypoint_file.exists());
+}
+
+#[tokio::test]
+async fn test_genesis_waypoint_encoding() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(1, &dir, &mut vec![]).await;
+    let (mut genesis_info, _) =
+        fetch_genesis_info(git_options, &GenesisOptions::default()).unwrap();
+    let waypoint = genesis_info.generate_waypoint().unwrap();
+    let genesis_bytes = bcs::to_bytes(genesis_info.get_genesis()).unwrap();
+
+    for encoding in [
+        WaypointEncoding::Plain,
+        WaypointEncoding::Hex,
+        WaypointEncoding::Base64,
+    ] {
+        assert_eq!(
+            encoding,
+            WaypointEncoding::from_str(&encoding.to_string()).unwrap()
+        );
+        assert_eq!(
+            waypoint,
+            encoding.decode(&encoding.encode(&waypoint)).unwrap()
+        );
+
+        let output_dir = TempPath::new();
+        output_dir.create_as_dir().unwrap();
+        let genesis_file = output_dir.path().join("genesis.blob");
+        let waypoint_file = output_dir.path().join("waypoint.txt");
+        write_genesis_outputs(
+            &genesis_file,
+            &waypoint_file,
+            &genesis_bytes,
+            waypoint,
+            encoding,
+        )
+        .unwrap();
+        let written = read_from_file(&waypoint_file).unwrap();
+        assert_eq!(waypoint, encoding.decode(&written).unwrap());
+    }
+
+    // The plain form is what was always written
+    assert_eq!(
+        waypoint.to_string().into_bytes(),
+        WaypointEncoding::Plain.encode(&waypoint)
+    );
+    assert!(WaypointEncoding::Hex
+        .decode(&WaypointEncoding::Base64.encode(&waypoint))
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_genesis_private_validator_address() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(2, &dir, &mut vec![]).await;
+
+    // Only publish the full node of user-0
+    let private_validator_config =
+        |full_node_host: Option<HostAndPort>| SetValidatorConfiguration {
+            username: "user-0".to_string(),
+            git_options: git_options.clone(),
+            owner_public_identity_file: Some(dir.path().join("0").join(PUBLIC_KEYS_FILE)),
+            validator_host: HostAndPort::from_str("localhost:6180").unwrap(),
+            stake_amount: 100_000_000_000_000,
+            full_node_host,
+            operator_public_identity_file: None,
+            voter_public_identity_file: None,
+            commission_percentage: 0,
+            join_during_genesis: true,
+            private_validator_address: true,
+        };
+    // Without a full node, there'd be nothing to connect to
+    assert!(private_validator_config(None).execute().await.is_err());
+    private_validator_config(Some(HostAndPort::from_str("localhost:6182").unwrap()))
+        .execute()
+        .await
+        .unwrap();
+    let private_owner = get_owner_address(git_options.clone(), 0);
+    let public_owner = get_owner_address(git_options.clone(), 1);
+
+    let output_dir = TempPath::new();
+    output_dir.create_as_dir().unwrap();
+    let output_dir = PathBuf::from(output_dir.path());
+    generate_genesis(git_options, output_dir.clone(), false).await;
+
+    // The private validator is left out of the validator seeds, but its full node is published
+    let seeds: GenesisSeeds = from_yaml(
+        &String::from_utf8(read_from_file(output_dir.join(SEEDS_FILE).as_path()).unwrap()).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(1, seeds.validator_network.len());
+    assert!(seeds.validator_network.contains_key(&public_owner));
+    assert_eq!(1, seeds.full_node_network.len());
+    assert_eq!(
+        PeerRole::Upstream,
+        seeds.full_node_network.values().next().unwrap().role
+    );
+
+    // The summary also leaves out the validator address
+    let summary: GenesisSummary = from_yaml(
+        &String::from_utf8(read_from_file(output_dir.join(SUMMARY_FILE).as_path()).unwrap())
+            .unwrap(),
+    )
+    .unwrap();
+    let private_summary = summary
+        .validators
+        .iter()
+        .find(|validator| validator.owner_account_address == private_owner)
+        .unwrap();
+    assert!(private_summary.validator_network_address.is_none());
+    assert!(private_summary.full_node_network_address.is_some());
+}
+
+#[tokio::test]
+async fn test_multi_ed25519_owner_key() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(2, &dir, &mut vec![]).await;
+    let client = git_options.clone().get_client().unwrap();
+
+    // Give the first user a 2 of 3 multisig owner account
+    let mut key_gen = KeyGen::from_seed([7; 32]);
+    let owner_key = MultiEd25519PublicKey::new(
+        (0..3)
+            .map(|_| key_gen.generate_ed25519_private_key().public_key())
+            .collect(),
+        2,
+    )
+    .unwrap();
+    let owner_file = Path::new("user-0").join(OWNER_FILE);
+    let mut owner_config: OwnerConfiguration = client.get(owner_file.as_path()).unwrap();
+    owner_config.owner_account_public_key = AccountPublicKey::from(owner_key.clone());
+    owner_config.owner_account_address =
+        AuthenticationKey::multi_ed25519(&owner_key).derived_address();
+    client.put(owner_file.as_path(), &owner_config).unwrap();
+
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+    let validators = get_validator_configs(&client, &layout, false, false).unwrap();
+    assert_eq!(
+        AccountPublicKey::MultiEd25519(owner_key.clone()),
+        validators[0].owner_account_public_key
+    );
+
+    let output_dir = TempPath::new();
+    output_dir.create_as_dir().unwrap();
+    generate_genesis(git_options.clone(), PathBuf::from(output_dir.path()), false).await;
+    assert!(output_dir.path().join("genesis.blob").exists());
+
+    // The address has to be derived with the multi-ed25519 scheme
+    owner_config.owner_account_address =
+        AuthenticationKey::ed25519(&owner_key.public_keys()[0]).derived_address();
+    client.put(owner_file.as_path(), &owner_config).unwrap();
+    let err = fetch_genesis_info(git_options, &GenesisOptions::default())
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string()
+            .contains("does not match account key derived"),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_get_validator_config() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let owner = get_owner_address(git_options.clone(), 0);
+
+    let report = GetValidatorConfig {
+        username: "user-0".to_string(),
+        mainnet: true,
+        yaml: false,
+        strict: false,
+        git_options: git_options.clone(),
+    }
+    .execute()
+    .await
+    .unwrap();
+    assert_eq!(owner.to_hex_literal(), report.owner_account_address);
+    assert_eq!(100_000_000_000_000, report.stake_amount);
+    assert!(report.passes_validation, "{:?}", report.validation_error);
+    // One of the 7 equally funded accounts in the balances file
+    assert_eq!(Some(10_000 / 7), report.stake_share_bps);
+    assert!(report.owner_account_public_key.contains(".."));
+
+    // An operator file missing a field fails the same way as genesis generation
+    let client = git_options.clone().get_client().unwrap();
+    let operator_file = Path::new("user-1").join(OPERATOR_FILE);
+    let mut operator_config: StringOperatorConfiguration =
+        client.get(operator_file.as_path()).unwrap();
+    operator_config.consensus_public_key = None;
+    client
+        .put(operator_file.as_path(), &operator_config)
+        .unwrap();
+
+    let get_validator_config = |yaml: bool| GetValidatorConfig {
+        username: "user-1".to_string(),
+        mainnet: true,
+        yaml,
+        strict: false,
+        git_options: git_options.clone(),
+    };
+    let err = get_validator_config(false).execute().await.unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Missing or invalid fields in file user-1/operator.yaml (operator file of user user-1): consensus_public_key is missing"),
+        "{}",
+        err
+    );
+
+    // And is reported the same way whether or not the output is YAML
+    let json_err = GenesisTool::GetValidatorConfig(get_validator_config(false))
+        .execute()
+        .await
+        .unwrap_err();
+    let yaml_err = GenesisTool::GetValidatorConfig(get_validator_config(true))
+        .execute()
+        .await
+        .unwrap_err();
+    assert_eq!(json_err, yaml_err);
+}
+
+#[tokio::test]
+async fn test_validate_user() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(2, &dir, &mut vec![]).await;
+    let validate_user = |user: &str, git_options: GitOptions| ValidateUser {
+        user: user.to_string(),
+        mainnet: false,
+        strict: false,
+        git_options,
+    };
+
+    let report = validate_user("user-0", git_options.clone())
+        .execute()
+        .await
+        .unwrap();
+    assert!(report.passes, "{:?}", report);
+    assert_eq!(
+        vec!["parse", "proof_of_possession", "hosts_and_keys", "genesis"],
+        report
+            .checks
+            .iter()
+            .map(|check| check.check.as_str())
+            .collect::<Vec<_>>()
+    );
+
+    // Another user's proof of possession parses, but isn't valid for the consensus key
+    let client = git_options.clone().get_client().unwrap();
+    let other_operator: StringOperatorConfiguration = client
+        .get(Path::new("user-1").join(OPERATOR_FILE).as_path())
+        .unwrap();
+    let operator_file = Path::new("user-0").join(OPERATOR_FILE);
+    let mut operator_config: StringOperatorConfiguration =
+        client.get(operator_file.as_path()).unwrap();
+    operator_config.consensus_proof_of_possession = other_operator.consensus_proof_of_possession;
+    client
+        .put(operator_file.as_path(), &operator_config)
+        .unwrap();
+    let report = validate_user("user-0", git_options.clone())
+        .execute()
+        .await
+        .unwrap();
+    assert!(!report.passes);
+    let failed: Vec<_> = report.checks.iter().filter(|check| !check.passed).collect();
+    assert_eq!(1, failed.len(), "{:?}", report);
+    assert_eq!("proof_of_possession", failed[0].check);
+    assert!(failed[0]
+        .error
+        .as_ref()
+        .unwrap()
+        .contains("consensus_proof_of_possession is not valid"));
+
+    // The command fails, but still prints the whole report
+    let err = GenesisTool::ValidateUser(validate_user("user-0", git_options.clone()))
+        .execute()
+        .await
+        .unwrap_err();
+    let output: serde_json::Value = serde_json::from_str(&err).unwrap();
+    assert_eq!(Some(false), output["Result"]["passes"].as_bool(), "{}", err);
+    assert_eq!(
+        4,
+        output["Result"]["checks"].as_array().unwrap().len(),
+        "{}",
+        err
+    );
+
+    // Files that don't parse can't be checked any further
+    operator_config.consensus_public_key = None;
+    client
+        .put(operator_file.as_path(), &operator_config)
+        .unwrap();
+    let report = validate_user("user-0", git_options.clone())
+        .execute()
+        .await
+        .unwrap();
+    assert_eq!(1, report.checks.len());
+    assert!(!report.checks[0].passed);
+    assert!(report.checks[0]
+        .error
+        .as_ref()
+        .unwrap()
+        .contains("consensus_public_key is missing"));
+
+    // The other user is unaffected, and users not in the layout are rejected
+    assert!(
+        validate_user("user-1", git_options.clone())
+            .execute()
+            .await
+            .unwrap()
+            .passes
+    );
+    assert!(validate_user("user-2", git_options)
+        .execute()
+        .await
+        .is_err());
+
+    // Mainnet users are also checked against the layout and balances
+    let mainnet_dir = TempPath::new();
+    mainnet_dir.create_as_dir().unwrap();
+    let report = ValidateUser {
+        user: "user-0".to_string(),
+        mainnet: true,
+        strict: false,
+        git_options: setup_mainnet_git_dir(&mainnet_dir, vec![]).await,
+    }
+    .execute()
+    .await
+    .unwrap();
+    assert!(report.passes, "{:?}", report);
+}
+
+#[tokio::test]
+async fn test_find_employee_account() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+
+    // The admin runs the validator of both pools, and can reset their beneficiaries
+    let admin_identity: PublicIdentity =
+        read_yaml_file(dir.path().join("2").join(PUBLIC_KEYS_FILE).as_path());
+    let matches = FindEmployeeAccount {
+        address: admin_identity.account_address,
+        git_options: git_options.clone(),
+    }
+    .execute()
+    .await
+    .unwrap();
+    assert_eq!(8, matches.len());
+    for pool_index in 0..2 {
+        let mut roles: Vec<_> = matches
+            .iter()
+            .filter(|found| found.pool_index == pool_index)
+            .map(|found| found.role.as_str())
+            .collect();
+        roles.sort_unstable();
+        assert_eq!(
+            vec!["beneficiary_resetter", "operator", "owner", "voter"],
+            roles
+        );
+    }
+    assert!(matches
+        .iter()
+        .all(|found| found.operator_account_address == admin_identity.account_address));
+
+    // An employee account is only in its own pool
+    let matches = FindEmployeeAccount {
+        address: AccountAddress::from_hex_literal("0x203").unwrap(),
+        git_options: git_options.clone(),
+    }
+    .execute()
+    .await
+    .unwrap();
+    assert_eq!(1, matches.len());
+    assert_eq!(1, matches[0].pool_index);
+    assert_eq!("account", matches[0].role);
+    assert_eq!(2 * INITIAL_BALANCE, matches[0].stake_amount);
+
+    let err = FindEmployeeAccount {
+        address: AccountAddress::from_hex_literal("0x999").unwrap(),
+        git_options,
+    }
+    .execute()
+    .await
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("is not in any of the 2 employee pools"),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_generate_beneficiary_reset_write_set() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let git_dir = git_options.local_repository_dir.as_ref().unwrap();
+    let employee_pools_file = git_dir.join(EMPLOYEE_VESTING_ACCOUNTS_FILE);
+    let employee_pools: EmployeePoolMap = read_yaml_file(employee_pools_file.as_path());
+    let admin = employee_pools.inner[0].validator.owner_account_address;
+    let beneficiary = AccountAddress::from_hex_literal("0x301").unwrap();
+
+    let output_dir = TempPath::new();
+    output_dir.create_as_dir().unwrap();
+    let resets_file = output_dir.path().join("resets.csv");
+    let output_file = output_dir.path().join("resets.json");
+    let command = || GenerateBeneficiaryResetWriteSet {
+        employee_pools_file: employee_pools_file.clone(),
+        resets_file: resets_file.clone(),
+        output_file: output_file.clone(),
+        prompt_options: PromptOptions::yes(),
+    };
+
+    // 0x203 is in the second pool, not the first
+    let rows = [
+        "pool,account,new_beneficiary",
+        "0,0x201,0x301",
+        "0,0x203,0x301",
+        "1,0x204,",
+    ];
+    write_to_file(&resets_file, "resets", rows.join("\n").as_bytes()).unwrap();
+    let err = command().execute().await.unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("row 3: account 0x203 is not in pool #0"),
+        "{}",
+        err
+    );
+    assert!(!output_file.exists());
+
+    let rows = ["0,0x201,0x301", "1,0x204,"];
+    write_to_file(&resets_file, "resets", rows.join("\n").as_bytes()).unwrap();
+    let transactions = command().execute().await.unwrap();
+    assert!(output_file.exists());
+    assert_eq!(2, transactions.len());
+
+    // Both pools share an admin, so their vesting contracts differ by the admin's nonce
+    assert_eq!(admin, transactions[0].sender);
+    assert_eq!(
+        create_vesting_contract_address(admin, 0, &[]),
+        transactions[0].contract_address
+    );
+    assert_eq!(Some(beneficiary), transactions[0].new_beneficiary);
+    assert_eq!("0x1::vesting::set_beneficiary", transactions[0].function);
+    assert_eq!(
+        create_vesting_contract_address(admin, 1, &[]),
+        transactions[1].contract_address
+    );
+    assert_eq!(None, transactions[1].new_beneficiary);
+    assert_eq!("0x1::vesting::reset_beneficiary", transactions[1].function);
+    assert_eq!(
+        format!(
+            "0x{}",
+            hex::encode(
+                bcs::to_bytes(&cached_packages::aptos_stdlib::vesting_reset_beneficiary(
+                    transactions[1].contract_address,
+                    AccountAddress::from_hex_literal("0x204").unwrap()
+                ))
+                .unwrap()
+            )
+        ),
+        transactions[1].payload
+    );
+}
+
+#[tokio::test]
+async fn test_consensus_key_reuse() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(2, &dir, &mut vec![]).await;
+    let client = git_options.clone().get_client().unwrap();
+
+    // Reuse user-0's consensus key for user-1
+    let user_0_config: StringOperatorConfiguration = client
+        .get(Path::new("user-0").join(OPERATOR_FILE).as_path())
+        .unwrap();
+    let operator_file = Path::new("user-1").join(OPERATOR_FILE);
+    let user_1_config: StringOperatorConfiguration = client.get(operator_file.as_path()).unwrap();
+    let reused_config = StringOperatorConfiguration {
+        consensus_public_key: user_0_config.consensus_public_key.clone(),
+        consensus_proof_of_possession: user_0_config.consensus_proof_of_possession,
+        ..user_1_config.clone()
+    };
+    client.put(operator_file.as_path(), &reused_config).unwrap();
+
+    let err = fetch_genesis_info(git_options.clone(), &GenesisOptions::default())
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string().contains(&format!(
+            "{} is used by user user-0, user user-1",
+            user_0_config.consensus_public_key.unwrap()
+        )),
+        "{}",
+        err
+    );
+
+    // A network key pasted in as the consensus key is called out
+    let misplaced_config = StringOperatorConfiguration {
+        consensus_public_key: user_1_config.validator_network_public_key.clone(),
+        ..user_1_config
+    };
+    client
+        .put(operator_file.as_path(), &misplaced_config)
+        .unwrap();
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+    let errors = get_validator_configs(&client, &layout, false, false).unwrap_err();
+    assert!(
+        errors[0].contains("looks like a network or account key rather than a consensus key"),
+        "{}",
+        errors[0]
+    );
+}
+
+#[tokio::test]
+async fn test_host_collisions() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(2, &dir, &mut vec![]).await;
+    let client = git_options.clone().get_client().unwrap();
+
+    // Point user-1's full node at user-0's validator
+    let operator_file = Path::new("user-1").join(OPERATOR_FILE);
+    let mut operator_config: StringOperatorConfiguration =
+        client.get(operator_file.as_path()).unwrap();
+    operator_config.validator_host = Some("localhost:6190".to_string());
+    operator_config.full_node_host = Some(HostAndPort::from_str("localhost:6180").unwrap());
+    client
+        .put(operator_file.as_path(), &operator_config)
+        .unwrap();
+
+    // Collisions are only a warning by default
+    fetch_genesis_info(git_options.clone(), &GenesisOptions::default()).unwrap();
+
+    let genesis_options = GenesisOptions {
+        fail_on_host_collisions: true,
+        ..Default::default()
+    };
+    let err = fetch_genesis_info(git_options, &genesis_options)
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string().contains(
+            "Full node host localhost:6180 of user user-1 is the validator host of user user-0"
+        ),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_config_errors_name_source() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(2, &dir, &mut vec![]).await;
+    let client = git_options.get_client().unwrap();
+
+    // The operator account is in both the owner and operator files
+    let owner_file = Path::new("user-0").join(OWNER_FILE);
+    let mut owner_config: StringOwnerConfiguration = client.get(owner_file.as_path()).unwrap();
+    owner_config.operator_account_address = Some("bad".to_string());
+    client.put(owner_file.as_path(), &owner_config).unwrap();
+    let operator_file = Path::new("user-1").join(OPERATOR_FILE);
+    let mut operator_config: StringOperatorConfiguration =
+        client.get(operator_file.as_path()).unwrap();
+    operator_config.operator_account_address = Some("bad".to_string());
+    client
+        .put(operator_file.as_path(), &operator_config)
+        .unwrap();
+
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+    let errors = get_validator_configs(&client, &layout, false, false).unwrap_err();
+    assert_eq!(2, errors.len());
+    assert!(errors.iter().any(|error| error.contains(
+        "Missing or invalid fields in file user-0/owner.yaml (owner file of user user-0): operator_account_address is invalid with value 'bad'"
+    )));
+    assert!(errors.iter().any(|error| error.contains(
+        "Missing or invalid fields in file user-1/operator.yaml (operator file of user user-1): operator_account_address is invalid with value 'bad'"
+    )));
+}
+
+#[tokio::test]
+async fn test_config_errors_report_all_fields() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(1, &dir, &mut vec![]).await;
+    let client = git_options.get_client().unwrap();
+
+    let operator_file = Path::new("user-0").join(OPERATOR_FILE);
+    let mut operator_config: StringOperatorConfiguration =
+        client.get(operator_file.as_path()).unwrap();
+    operator_config.consensus_public_key = None;
+    operator_config.consensus_proof_of_possession = None;
+    operator_config.validator_network_public_key = None;
+    operator_config.validator_host = None;
+    operator_config.publish_validator_address = Some("maybe".to_string());
+    client
+        .put(operator_file.as_path(), &operator_config)
+        .unwrap();
+
+    // All of the problems are in the one entry for the user
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+    let errors = get_validator_configs(&client, &layout, false, false).unwrap_err();
+    assert_eq!(1, errors.len());
+    for problem in [
+        "consensus_public_key is missing",
+        "consensus_proof_of_possession is missing",
+        "validator_network_public_key is missing",
+        "validator_host is missing",
+        "publish_validator_address is invalid with value 'maybe'",
+    ] {
+        assert!(errors[0].contains(problem), "{}", errors[0]);
+    }
+}
+
+#[tokio::test]
+async fn test_yml_owner_file() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(1, &dir, &mut vec![]).await;
+    let git_dir = git_options.local_repository_dir.clone().unwrap();
+    let client = git_options.get_client().unwrap();
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+    let expected = get_validator_configs(&client, &layout, false, false).unwrap();
+
+    // The owner file can also use the short YAML extension
+    let owner_file = git_dir.join("user-0").join(OWNER_FILE);
+    let yml_owner_file = owner_file.with_extension("yml");
+    std::fs::rename(&owner_file, &yml_owner_file).unwrap();
+    let validators = get_validator_configs(&client, &layout, false, false).unwrap();
+    assert_eq!(
+        expected[0].owner_account_address,
+        validators[0].owner_account_address
+    );
+
+    // But not alongside another owner file
+    std::fs::copy(&yml_owner_file, &owner_file).unwrap();
+    let err = get_validator_configs(&client, &layout, false, false)
+        .err()
+        .unwrap();
+    assert!(
+        format!("{:?}", err).contains("only one of them"),
+        "{:?}",
+        err
+    );
+    assert!(format!("{:?}", err).contains("owner.yml"), "{:?}", err);
+}
+
+#[tokio::test]
+async fn test_json_owner_file() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(1, &dir, &mut vec![]).await;
+    let git_dir = git_options.local_repository_dir.clone().unwrap();
+    let client = git_options.get_client().unwrap();
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+    let expected = get_validator_configs(&client, &layout, false, false).unwrap();
+
+    // Replace the owner file with a JSON one, and keep the YAML operator file
+    let owner_file = Path::new("user-0").join(OWNER_FILE);
+    let owner_config: StringOwnerConfiguration = client.get(owner_file.as_path()).unwrap();
+    let json_owner_file = git_dir.join("user-0").join(OWNER_JSON_FILE);
+    write_to_file(
+        json_owner_file.as_path(),
+        OWNER_JSON_FILE,
+        serde_json::to_string_pretty(&owner_config)
+            .unwrap()
+            .as_bytes(),
+    )
+    .unwrap();
+
+    // Only one of the formats can be used
+    let err = get_validator_configs(&client, &layout, false, false)
+        .err()
+        .unwrap();
+    assert!(
+        format!("{:?}", err).contains("only one of them"),
+        "{:?}",
+        err
+    );
+
+    std::fs::remove_file(git_dir.join(owner_file)).unwrap();
+    let validators = get_validator_configs(&client, &layout, false, false).unwrap();
+    assert_eq!(
+        expected[0].owner_account_address,
+        validators[0].owner_account_address
+    );
+    assert_eq!(
+        expected[0].owner_account_public_key,
+        validators[0].owner_account_public_key
+    );
+    assert_eq!(expected[0].stake_amount, validators[0].stake_amount);
+    assert_eq!(
+        expected[0].consensus_public_key,
+        validators[0].consensus_public_key
+    );
+    fetch_genesis_info(
+        GitOptions {
+            local_repository_dir: Some(git_dir.clone()),
+            ..Default::default()
+        },
+        &GenesisOptions::default(),
+    )
+    .unwrap();
+
+    // Errors point at the JSON file
+    let mut owner_config = owner_config;
+    owner_config.owner_account_address = Some("not an address".to_string());
+    write_to_file(
+        json_owner_file.as_path(),
+        OWNER_JSON_FILE,
+        serde_json::to_string_pretty(&owner_config)
+            .unwrap()
+            .as_bytes(),
+    )
+    .unwrap();
+    let err = get_validator_configs(&client, &layout, false, false)
+        .err()
+        .unwrap();
+    assert!(format!("{:?}", err).contains(OWNER_JSON_FILE), "{:?}", err);
+}
+
+#[tokio::test]
+async fn test_config_values_are_cleaned() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = create_users(1, &dir, &mut vec![]).await;
+    let client = git_options.get_client().unwrap();
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+    let expected = get_validator_configs(&client, &layout, false, false).unwrap();
+
+    // A trailing newline from a block scalar, and addresses without their prefix
+    let owner_file = Path::new("user-0").join(OWNER_FILE);
+    let mut owner_config: StringOwnerConfiguration = client.get(owner_file.as_path()).unwrap();
+    let owner_account_public_key = owner_config.owner_account_public_key.clone().unwrap();
+    owner_config.owner_account_public_key = Some(format!("{}\n", owner_account_public_key));
+    owner_config.owner_account_address = owner_config
+        .owner_account_address
+        .map(|address| format!(" {} ", address.trim_start_matches("0x")));
+    client.put(owner_file.as_path(), &owner_config).unwrap();
+    let operator_file = Path::new("user-0").join(OPERATOR_FILE);
+    let mut operator_config: StringOperatorConfiguration =
+        client.get(operator_file.as_path()).unwrap();
+    operator_config.operator_account_address = operator_config
+        .operator_account_address
+        .map(|address| address.trim_start_matches("0x").to_string());
+    client
+        .put(operator_file.as_path(), &operator_config)
+        .unwrap();
+
+    let validators = get_validator_configs(&client, &layout, false, false).unwrap();
+    assert_eq!(
+        expected[0].owner_account_address,
+        validators[0].owner_account_address
+    );
+    assert_eq!(
+        expected[0].operator_account_address,
+        validators[0].operator_account_address
+    );
+    assert_eq!(
+        expected[0].owner_account_public_key,
+        validators[0].owner_account_public_key
+    );
+
+    // A truncated key reports its length, so the missing characters are obvious
+    let truncated_key = &owner_account_public_key[..owner_account_public_key.len() - 2];
+    owner_config.owner_account_public_key = Some(truncated_key.to_string());
+    client.put(owner_file.as_path(), &owner_config).unwrap();
+    let errors = get_validator_configs(&client, &layout, false, false).unwrap_err();
+    let hint = format!(
+        "({} characters, starting with '{}' and ending with '{}')",
+        truncated_key.len(),
+        &truncated_key[..1],
+        &truncated_key[truncated_key.len() - 1..]
+    );
+    assert!(errors[0].contains(&hint), "{}", errors[0]);
+
+    // As does a truncated address, rather than it being padded into another account
+    let owner_account_address = expected[0].owner_account_address.to_hex();
+    let truncated_address = &owner_account_address[..owner_account_address.len() - 2];
+    owner_config.owner_account_public_key = Some(owner_account_public_key);
+    owner_config.owner_account_address = Some(truncated_address.to_string());
+    client.put(owner_file.as_path(), &owner_config).unwrap();
+    let errors = get_validator_configs(&client, &layout, false, false).unwrap_err();
+    assert!(errors[0].contains("owner_account_address"), "{}", errors[0]);
+    let hint = format!(
+        "({} characters, starting with '{}' and ending with '{}')",
+        truncated_address.len(),
+        &truncated_address[..1],
+        &truncated_address[truncated_address.len() - 1..]
+    );
+    assert!(errors[0].contains(&hint), "{}", errors[0]);
+}
+
+#[tokio::test]
+async fn test_mainnet_genesis_unreferenced_balances() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let orphan = AccountAddress::from_hex_literal("0x101").unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![orphan]).await;
+
+    // The orphaned account should be reported with its balance
+    let (_, summary) =
+        fetch_mainnet_genesis_info(git_options.clone(), &GenesisOptions::default()).unwrap();
+    let unreferenced_balances = summary.unreferenced_balances.unwrap();
+    assert_eq!(1, unreferenced_balances.accounts.len());
+    assert_eq!(
+        Some(&INITIAL_BALANCE),
+        unreferenced_balances.accounts.get(&orphan)
+    );
+    assert_eq!(INITIAL_BALANCE as u128, unreferenced_balances.total_balance);
+
+    // And it should fail when unreferenced balances aren't allowed
+    let genesis_options = GenesisOptions {
+        fail_on_unreferenced_balances: true,
+        ..Default::default()
+    };
+    assert!(fetch_mainnet_genesis_info(git_options, &genesis_options).is_err());
+}
+
+#[test]
+fn test_parse_amount() {
+    // Octas with or without separators
+    assert_eq!(
+        100_000_000_000_000,
+        parse_amount("100000000000000").unwrap()
+    );
+    assert_eq!(
+        100_000_000_000_000,
+        parse_amount("100_000_000_000_000").unwrap()
+    );
+    assert_eq!(
+        100_000_000_000_000,
+        parse_amount(" 100,000,000,000,000 ").unwrap()
+    );
+    assert_eq!(1, parse_amount("1").unwrap());
+    assert_eq!(u64::MAX, parse_amount(&u64::MAX.to_string()).unwrap());
+
+    // APT with up to 8 decimal places
+    assert_eq!(OCTAS_PER_APT, parse_amount("1 APT").unwrap());
+    assert_eq!(OCTAS_PER_APT, parse_amount("1APT").unwrap());
+    assert_eq!(100_050_000_000, parse_amount("1_000.5 APT").unwrap());
+    assert_eq!(100_050_000_000, parse_amount("1,000.50 APT").unwrap());
+    assert_eq!(1, parse_amount("0.00000001 APT").unwrap());
+    assert_eq!(99_999_999, parse_amount("0.99999999 APT").unwrap());
+    assert_eq!(
+        u64::MAX,
+        parse_amount("184_467_440_737.09551615 APT").unwrap()
+    );
+
+    // Fractions of an octa aren't rounded
+    for amount in ["0.000000001 APT", "0.123456785 APT", "1.5", "1_000.5"] {
+        let err = parse_amount(amount).unwrap_err().to_string();
+        assert!(err.contains("1_000.5 APT"), "{}", err);
+    }
+
+    // Ambiguous, mixed, or malformed amounts
+    for amount in [
+        "1,000_000",
+        "1,00",
+        "1_0000",
+        "_100",
+        "100_",
+        "1000,000",
+        "1,5 APT",
+        "1. APT",
+        ".5 APT",
+        "-1",
+        "1e8",
+        "1 apt",
+        "",
+    ] {
+        assert!(parse_amount(amount).is_err(), "{}", amount);
+    }
+
+    // Overflow
+    assert!(parse_amount("18446744073709551616").is_err());
+    assert!(parse_amount("184467440737.09551616 APT").is_err());
+    assert!(parse_amount("184467440738 APT").is_err());
+
+    // Numbers only take separators
+    assert_eq!(1_000, parse_number("1,000").unwrap());
+    assert!(parse_number("10 APT").is_err());
+}
+
+#[tokio::test]
+async fn test_account_keys_match_addresses() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let client = git_options.get_client().unwrap();
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+    let validators = get_validator_configs(&client, &layout, true, false).unwrap();
+
+    // A voter key copied from another user's file doesn't derive the voter address
+    let owner_file = Path::new(&layout.users[0]).join(OWNER_FILE);
+    let owner_config: StringOwnerConfiguration = client.get(owner_file.as_path()).unwrap();
+    let other_config: StringOwnerConfiguration = client
+        .get(Path::new(&layout.users[1]).join(OWNER_FILE).as_path())
+        .unwrap();
+    let swapped_config = StringOwnerConfiguration {
+        voter_account_public_key: other_config.voter_account_public_key,
+        ..owner_config
+    };
+    client.put(owner_file.as_path(), &swapped_config).unwrap();
+
+    // Which is only a warning unless it's strict
+    get_validator_configs(&client, &layout, true, false).unwrap();
+    let errors = get_validator_configs(&client, &layout, true, true).unwrap_err();
+    assert_eq!(1, errors.len(), "{:?}", errors);
+    assert!(
+        errors[0].contains(&format!(
+            "voter_account_address {} does not match",
+            validators[0].voter_account_address
+        )),
+        "{}",
+        errors[0]
+    );
+    assert!(
+        !errors[0].contains("owner_account_address"),
+        "{}",
+        errors[0]
+    );
+}
+
+#[tokio::test]
+async fn test_get_validator_configs_partial() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let client = git_options.get_client().unwrap();
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+    let expected = get_validator_configs(&client, &layout, true, false).unwrap();
+    let (validators, errors) = get_validator_configs_partial(&client, &layout, true, false);
+    assert_eq!(2, validators.len());
+    assert!(errors.is_empty());
+
+    // The second user's validator is reported, and the first is still loaded
+    let owner_file = Path::new(&layout.users[1]).join(OWNER_FILE);
+    let mut owner_config: StringOwnerConfiguration = client.get(owner_file.as_path()).unwrap();
+    owner_config.stake_amount = Some("lots".to_string());
+    client.put(owner_file.as_path(), &owner_config).unwrap();
+    let (validators, errors) = get_validator_configs_partial(&client, &layout, true, false);
+    assert_eq!(1, validators.len());
+    assert_eq!(
+        expected[0].owner_account_address,
+        validators[0].owner_account_address
+    );
+    assert_eq!(1, errors.len());
+    assert_eq!(layout.users[1], errors[0].user);
+    assert!(errors[0].error.contains("stake_amount"), "{}", errors[0]);
+
+    // The strict loader fails with the same errors
+    assert_eq!(
+        vec![errors[0].to_string()],
+        get_validator_configs(&client, &layout, true, false).unwrap_err()
+    );
+}
+
+#[tokio::test]
+async fn test_joining_validator_without_stake() {
+    for mainnet in [false, true] {
+        let dir = TempPath::new();
+        dir.create_as_dir().unwrap();
+        let git_options = if mainnet {
+            setup_mainnet_git_dir(&dir, vec![]).await
+        } else {
+            create_users(2, &dir, &mut vec![]).await
+        };
+        let client = git_options.clone().get_client().unwrap();
+
+        // A min_stake of 0 lets the stake through the layout's bounds
+        let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+        client
+            .put(
+                Path::new(LAYOUT_FILE),
+                &Layout {
+                    min_stake: 0,
+                    ..layout.clone()
+                },
+            )
+            .unwrap();
+        let owner_file = Path::new(&layout.users[0]).join(OWNER_FILE);
+        let mut owner_config: StringOwnerConfiguration = client.get(owner_file.as_path()).unwrap();
+        owner_config.stake_amount = Some("0".to_string());
+        client.put(owner_file.as_path(), &owner_config).unwrap();
+
+        let err = if mainnet {
+            fetch_mainnet_genesis_info(git_options, &GenesisOptions::default())
+                .err()
+                .unwrap()
+        } else {
+            fetch_genesis_info(git_options, &GenesisOptions::default())
+                .err()
+                .unwrap()
+        };
+        assert!(
+            err.to_string().contains("stake_amount of 0"),
+            "mainnet {}: {}",
+            mainnet,
+            err
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_human_readable_amounts() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let client = git_options.clone().get_client().unwrap();
+
+    // Write the first user's stake in APT, and every balance with separators
+    let owner_file = Path::new("user-0").join(OWNER_FILE);
+    let mut owner_config: StringOwnerConfiguration = client.get(owner_file.as_path()).unwrap();
+    owner_config.stake_amount = Some("1_000_000 APT".to_string());
+    client.put(owner_file.as_path(), &owner_config).unwrap();
+    let balances: AccountBalanceMap = client.get(Path::new(BALANCES_FILE)).unwrap();
+    let balances: Vec<BTreeMap<AccountAddress, String>> = balances
+        .account_balances
+        .into_iter()
+        .map(|entry| {
+            entry
+                .into_keys()
+                .map(|address| (address, "100,000,000,000,000".to_string()))
+                .collect()
+        })
+        .collect();
+    client.put(Path::new(BALANCES_FILE), &balances).unwrap();
+
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+    let validators = get_validator_configs(&client, &layout, true, false).unwrap();
+    assert_eq!(INITIAL_BALANCE, validators[0].stake_amount);
+    let (_, summary) =
+        fetch_mainnet_genesis_info(git_options.clone(), &GenesisOptions::default()).unwrap();
+    let owner = get_owner_address(git_options.clone(), 0);
+    let validator_summary = summary
+        .validators
+        .iter()
+        .find(|validator| validator.owner_account_address == owner)
+        .unwrap();
+    assert_eq!(INITIAL_BALANCE, validator_summary.stake_amount);
+
+    // Malformed amounts are reported with the accepted forms
+    owner_config.stake_amount = Some("1.000.000 APT".to_string());
+    client.put(owner_file.as_path(), &owner_config).unwrap();
+    let err = fetch_mainnet_genesis_info(git_options, &GenesisOptions::default())
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string().contains("stake_amount is invalid"),
+        "{}",
+        err
+    );
+    assert!(err.to_string().contains("1_000.5 APT"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_mainnet_balances_directory() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let git_dir = git_options.local_repository_dir.clone().unwrap();
+
+    // Split the balances file into a YAML file and a CSV file
+    let balances: AccountBalanceMap = read_yaml_file(&git_dir.join(BALANCES_FILE));
+    let mut accounts: Vec<AccountBalance> = balances.try_into().unwrap();
+    let csv_accounts = accounts.split_off(accounts.len() / 2);
+    std::fs::remove_file(git_dir.join(BALANCES_FILE)).unwrap();
+    let balances_dir = git_dir.join(BALANCES_DIR);
+    std::fs::create_dir(&balances_dir).unwrap();
+    let yaml_balances = AccountBalanceMap::try_from(accounts.clone()).unwrap();
+    write_to_file(
+        &balances_dir.join("a.yaml"),
+        "a.yaml",
+        to_yaml(&yaml_balances).unwrap().as_bytes(),
+    )
+    .unwrap();
+    let mut csv = "address,balance\n".to_string();
+    for account in &csv_accounts {
+        csv.push_str(&format!(
+            "{},{}\n",
+            account.account_address, account.balance
+        ));
+    }
+    write_to_file(&balances_dir.join("b.csv"), "b.csv", csv.as_bytes()).unwrap();
+
+    let (_, summary) =
+        fetch_mainnet_genesis_info(git_options.clone(), &GenesisOptions::default()).unwrap();
+    assert_eq!(2, summary.balances_files.len());
+    let csv_summary = &summary.balances_files[1];
+    assert_eq!("balances/b.csv", csv_summary.file);
+    assert_eq!(csv_accounts.len(), csv_summary.num_accounts);
+    assert_eq!(file_hash(csv.as_bytes()), csv_summary.hash);
+
+    // An account can't get a balance from two files
+    csv.push_str(&format!(
+        "{},{}\n",
+        accounts[0].account_address, accounts[0].balance
+    ));
+    write_to_file(&balances_dir.join("b.csv"), "b.csv", csv.as_bytes()).unwrap();
+    let err = fetch_mainnet_genesis_info(git_options, &GenesisOptions::default())
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string()
+            .contains("balances files balances/a.yaml and balances/b.csv"),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_merge_repos() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let validators_dir = git_options.local_repository_dir.clone().unwrap();
+
+    // Move the balances and employee pools into their own repository
+    let balances_dir = TempPath::new();
+    balances_dir.create_as_dir().unwrap();
+    for file in [BALANCES_FILE, EMPLOYEE_VESTING_ACCOUNTS_FILE] {
+        std::fs::rename(validators_dir.join(file), balances_dir.path().join(file)).unwrap();
+    }
+
+    let merge = |output_dir: &TempPath| MergeRepos {
+        sources: vec![
+            GitSource::Local(validators_dir.clone()),
+            GitSource::Local(PathBuf::from(balances_dir.path())),
+        ],
+        output_dir: PathBuf::from(output_dir.path()),
+        github_token_file: None,
+        github_token_env: None,
+        github_api_url: None,
+        ca_cert_file: None,
+        prompt_options: PromptOptions::yes(),
+    };
+    let output_dir = TempPath::new();
+    output_dir.create_as_dir().unwrap();
+    let files = merge(&output_dir).execute().await.unwrap();
+    assert!(files.contains(&PathBuf::from(BALANCES_FILE)));
+    assert!(files.contains(&Path::new("user-0").join(OWNER_FILE)));
+    let merged_options = GitOptions {
+        local_repository_dir: Some(PathBuf::from(output_dir.path())),
+        ..Default::default()
+    };
+    fetch_mainnet_genesis_info(merged_options, &GenesisOptions::default()).unwrap();
+
+    // A user can only be defined in one source
+    std::fs::copy(
+        validators_dir.join(LAYOUT_FILE),
+        balances_dir.path().join(LAYOUT_FILE),
+    )
+    .unwrap();
+    std::fs::create_dir(balances_dir.path().join("user-0")).unwrap();
+    std::fs::copy(
+        validators_dir.join("user-0").join(OWNER_FILE),
+        balances_dir.path().join("user-0").join(OWNER_FILE),
+    )
+    .unwrap();
+    let output_dir = TempPath::new();
+    output_dir.create_as_dir().unwrap();
+    let err = merge(&output_dir).execute().await.unwrap_err();
+    assert!(
+        err.to_string().contains("User user-0 is defined in both"),
+        "{}",
+        err
+    );
+    assert!(!output_dir.path().join(LAYOUT_FILE).exists());
+}
+
+#[tokio::test]
+async fn test_mainnet_employee_accounts_unique() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let client = git_options.clone().get_client().unwrap();
+
+    // Add an employee from the first pool to the second pool as well
+    let mut employee_pools: EmployeePoolMap = client
+        .get(Path::new(EMPLOYEE_VESTING_ACCOUNTS_FILE))
+        .unwrap();
+    let employee = employee_pools.inner[0].accounts[0];
+    employee_pools.inner[1].accounts.push(employee);
+    client
+        .put(Path::new(EMPLOYEE_VESTING_ACCOUNTS_FILE), &employee_pools)
+        .unwrap();
+
+    let err = fetch_mainnet_genesis_info(git_options, &GenesisOptions::default())
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string()
+            .contains(&format!("Account {} is in employee pools #0, #1", employee)),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_mainnet_staked_supply_bounds() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    // Two standalone validators and one joining employee pool stake 4 of the 8 balances
+    let orphan = AccountAddress::from_hex_literal("0x101").unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![orphan]).await;
+    let client = git_options.clone().get_client().unwrap();
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+
+    let (_, summary) =
+        fetch_mainnet_genesis_info(git_options.clone(), &GenesisOptions::default()).unwrap();
+    assert_eq!(Some(5_000), summary.staked_supply_bps);
+
+    // The bounds are inclusive
+    for (min, max, passes) in [
+        (Some(50), Some(50), true),
+        (Some(51), None, false),
+        (None, Some(49), false),
+        (Some(0), Some(100), true),
+    ] {
+        let bounded_layout = Layout {
+            min_staked_supply_percent: min,
+            max_staked_supply_percent: max,
+            ..layout.clone()
+        };
+        client.put(Path::new(LAYOUT_FILE), &bounded_layout).unwrap();
+        let result = fetch_mainnet_genesis_info(git_options.clone(), &GenesisOptions::default());
+        assert_eq!(passes, result.is_ok(), "min {:?} max {:?}", min, max);
+    }
+
+    // Taking an octa from the orphan puts the stake just over half, which still shows as 5000 bps
+    let mut balances: AccountBalanceMap = client.get(Path::new(BALANCES_FILE)).unwrap();
+    for entry in balances.account_balances.iter_mut() {
+        if let Some(balance) = entry.get_mut(&orphan) {
+            *balance -= 1;
+        }
+    }
+    client.put(Path::new(BALANCES_FILE), &balances).unwrap();
+    let bounded_layout = Layout {
+        total_supply: Some(layout.total_supply.unwrap() - 1),
+        min_staked_supply_percent: None,
+        max_staked_supply_percent: Some(50),
+        ..layout.clone()
+    };
+    client.put(Path::new(LAYOUT_FILE), &bounded_layout).unwrap();
+    let err = fetch_mainnet_genesis_info(git_options.clone(), &GenesisOptions::default())
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string()
+            .contains("(5000 bps) is over max_staked_supply_percent 50%"),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_mainnet_supply_mismatch() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let client = git_options.clone().get_client().unwrap();
+    let (_, summary) =
+        fetch_mainnet_genesis_info(git_options.clone(), &GenesisOptions::default()).unwrap();
+    let num_accounts: usize = summary
+        .balances_files
+        .iter()
+        .map(|file| file.num_accounts)
+        .sum();
+
+    // Expect one more than the balances add up to
+    let mut layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+    layout.total_supply = layout.total_supply.map(|supply| supply + 1);
+    client.put(Path::new(LAYOUT_FILE), &layout).unwrap();
+    let err = fetch_mainnet_genesis_info(git_options.clone(), &GenesisOptions::default())
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(err.contains("(1 under)"), "{}", err);
+    assert!(
+        err.contains(&format!("across {} accounts", num_accounts)),
+        "{}",
+        err
+    );
+    assert!(
+        err.contains(&format!(
+            "with balances from {} to {}",
+            INITIAL_BALANCE, INITIAL_BALANCE
+        )),
+        "{}",
+        err
+    );
+    assert!(!err.contains("written to"), "{}", err);
+
+    // The breakdown is a balances CSV, sorted by address
+    let debug_dir = TempPath::new();
+    debug_dir.create_as_dir().unwrap();
+    let debug_file = debug_dir.path().join("supply.csv");
+    let options = GenesisOptions {
+        debug_supply: Some(debug_file.clone()),
+        ..Default::default()
+    };
+    let err = fetch_mainnet_genesis_info(git_options, &options)
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(
+        err.contains(&format!("written to {}", debug_file.display())),
+        "{}",
+        err
+    );
+    let breakdown =
+        parse_balances_csv("supply.csv", &std::fs::read_to_string(debug_file).unwrap()).unwrap();
+    assert_eq!(num_accounts, breakdown.len());
+    assert!(breakdown
+        .windows(2)
+        .all(|pair| pair[0].account_address < pair[1].account_address));
+    assert!(breakdown
+        .iter()
+        .all(|account| account.balance == INITIAL_BALANCE));
+}
+
+#[tokio::test]
+async fn test_mainnet_sort_accounts() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let client = git_options.clone().get_client().unwrap();
+    let sorted = GenesisOptions {
+        sort_accounts: true,
+        ..Default::default()
+    };
+    let (mut genesis_info, _) = fetch_mainnet_genesis_info(git_options.clone(), &sorted).unwrap();
+    let waypoint = genesis_info.generate_waypoint().unwrap();
+
+    // The same balances in the opposite order give the same genesis once sorted
+    let mut balances: AccountBalanceMap = client.get(Path::new(BALANCES_FILE)).unwrap();
+    balances.account_balances.reverse();
+    client.put(Path::new(BALANCES_FILE), &balances).unwrap();
+    let (mut reordered_info, _) = fetch_mainnet_genesis_info(git_options.clone(), &sorted).unwrap();
+    assert_eq!(waypoint, reordered_info.generate_waypoint().unwrap());
+}
+
+#[test]
+fn test_nakamoto_coefficients() {
+    let validator =
+        |operator: u64, stake_amount: u64, join_during_genesis: bool| ValidatorSummary {
+            owner_account_address: AccountAddress::random(),
+            operator_account_address: AccountAddress::from_hex_literal(&format!("0x{}", operator))
+                .unwrap(),
+            stake_amount,
+            commission_percentage: 0,
+            join_during_genesis,
+            employee_pool: None,
+            validator_network_address: None,
+            full_node_network_address: None,
+        };
+
+    // Operator 1 runs two validators, and operator 6 doesn't join so has no voting power
+    let validators = vec![
+        validator(1, 10, true),
+        validator(1, 10, true),
+        validator(2, 15, true),
+        validator(3, 5, true),
+        validator(4, 5, true),
+        validator(5, 5, true),
+        validator(6, 100, false),
+    ];
+    assert_eq!(
+        Some(NakamotoCoefficients {
+            third: 1,
+            two_thirds: 2
+        }),
+        nakamoto_coefficients(&validators)
+    );
+
+    // Exactly a third or two thirds isn't more than it
+    let validators = vec![
+        validator(1, 10, true),
+        validator(2, 10, true),
+        validator(3, 10, true),
+    ];
+    assert_eq!(
+        Some(NakamotoCoefficients {
+            third: 2,
+            two_thirds: 3
+        }),
+        nakamoto_coefficients(&validators)
+    );
+
+    assert_eq!(None, nakamoto_coefficients(&[validator(1, 10, false)]));
+}
+
+#[test]
+fn test_commission_stats() {
+    let validator = |commission_percentage: u64, join_during_genesis: bool| ValidatorSummary {
+        owner_account_address: AccountAddress::random(),
+        operator_account_address: AccountAddress::random(),
+        stake_amount: 1,
+        commission_percentage,
+        join_during_genesis,
+        employee_pool: None,
+        validator_network_address: None,
+        full_node_network_address: None,
+    };
+
+    // The validator at 100% doesn't join, so isn't counted
+    let validators = vec![
+        validator(10, true),
+        validator(0, true),
+        validator(5, true),
+        validator(8, true),
+        validator(100, false),
+    ];
+    assert_eq!(
+        Some(CommissionStats {
+            min: 0,
+            median: 5,
+            max: 10,
+            mean: 5
+        }),
+        commission_stats(&validators)
+    );
+
+    assert_eq!(None, commission_stats(&[validator(10, false)]));
+}
+
+#[tokio::test]
+async fn test_mainnet_min_nakamoto_coefficient() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    // The employee pool's operator holds half of the voting power on its own
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let client = git_options.clone().get_client().unwrap();
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+
+    let (_, summary) =
+        fetch_mainnet_genesis_info(git_options.clone(), &GenesisOptions::default()).unwrap();
+    assert_eq!(
+        Some(NakamotoCoefficients {
+            third: 1,
+            two_thirds: 2
+        }),
+        summary.nakamoto_coefficients
+    );
+
+    for (min, passes) in [(1, true), (2, false)] {
+        let bounded_layout = Layout {
+            min_nakamoto_coefficient_third: Some(min),
+            ..layout.clone()
+        };
+        client.put(Path::new(LAYOUT_FILE), &bounded_layout).unwrap();
+        let result = fetch_mainnet_genesis_info(git_options.clone(), &GenesisOptions::default());
+        assert_eq!(passes, result.is_ok(), "min {}", min);
+    }
+}
+
+#[tokio::test]
+async fn test_resolve_hosts() {
+    // IP addresses are kept, and names that can't be resolved fail
+    let ip = HostAndPort::from_str("127.0.0.1:6180").unwrap();
+    assert_eq!(ip, ip.resolve().unwrap());
+    assert!(HostAndPort::from_str("unknown.invalid:6180")
+        .unwrap()
+        .resolve()
+        .is_err());
+
+    // Names with several addresses prefer IPv4, whatever order the resolver returns them in
+    let has_ipv4 = ("localhost", 6180)
+        .to_socket_addrs()
+        .unwrap()
+        .any(|address| address.is_ipv4());
+    let localhost = HostAndPort::from_str("localhost:6180").unwrap();
+    let resolved = localhost.resolve().unwrap();
+    assert_eq!(resolved, localhost.resolve().unwrap());
+    assert_eq!(
+        has_ipv4,
+        IpAddr::from_str(&resolved.host.to_string())
+            .unwrap()
+            .is_ipv4()
+    );
+
+    // The test validators are all on localhost
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let (_, summary) =
+        fetch_mainnet_genesis_info(git_options.clone(), &GenesisOptions::default()).unwrap();
+    let addresses = |summary: &GenesisSummary| {
+        summary
+            .validators
+            .iter()
+            .flat_map(|validator| validator.validator_network_address.iter())
+            .map(|address| address.to_string())
+            .collect::<Vec<_>>()
+    };
+    assert!(!addresses(&summary).is_empty());
+    assert!(addresses(&summary)
+        .iter()
+        .all(|address| address.starts_with("/dns/localhost/")));
+
+    let resolve_hosts = GenesisOptions {
+        resolve_hosts: true,
+        ..Default::default()
+    };
+    let (_, resolved_summary) = fetch_mainnet_genesis_info(git_options, &resolve_hosts).unwrap();
+    let resolved = addresses(&resolved_summary);
+    assert_eq!(addresses(&summary).len(), resolved.len());
+    assert!(
+        resolved
+            .iter()
+            .all(|address| address.starts_with("/ip4/") || address.starts_with("/ip6/")),
+        "{:?}",
+        resolved
+    );
+}
+
+#[tokio::test]
+async fn test_load_and_validate_layout() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let client = git_options.clone().get_client().unwrap();
+    let layout = load_and_validate_layout(&client, true).unwrap();
+
+    // Every problem is reported at once
+    let invalid_layout = Layout {
+        epoch_duration_secs: 0,
+        users: vec![
+            layout.users[0].clone(),
+            layout.users[1].clone(),
+            layout.users[0].clone(),
+        ],
+        total_supply: None,
+        root_key: None,
+        ..layout
+    };
+    client.put(Path::new(LAYOUT_FILE), &invalid_layout).unwrap();
+    let err = load_and_validate_layout(&client, true)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("epoch_duration_secs must be greater than 0"),
+        "{}",
+        err
+    );
+    assert!(
+        err.contains(&format!(
+            "users are listed more than once: {}",
+            invalid_layout.users[0]
+        )),
+        "{}",
+        err
+    );
+    assert!(err.contains("total_supply must be set"), "{}", err);
+    assert!(!err.contains("root_key"), "{}", err);
+    let err = load_and_validate_layout(&client, false)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("root_key was not set"), "{}", err);
+    assert!(!err.contains("total_supply"), "{}", err);
+
+    // Genesis uses the same checks
+    let err = fetch_mainnet_genesis_info(git_options, &GenesisOptions::default())
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("epoch_duration_secs"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_mainnet_max_commission_percentage() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    // The standalone validators charge 10% and 0%
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let client = git_options.clone().get_client().unwrap();
+    let layout: Layout = client.get(Path::new(LAYOUT_FILE)).unwrap();
+
+    for (max, passes) in [(100, true), (10, true), (9, false), (0, false)] {
+        let capped_layout = Layout {
+            max_commission_percentage: Some(max),
+            ..layout.clone()
+        };
+        client.put(Path::new(LAYOUT_FILE), &capped_layout).unwrap();
+        let result = fetch_mainnet_genesis_info(git_options.clone(), &GenesisOptions::default());
+        assert_eq!(passes, result.is_ok(), "max {}", max);
+    }
+
+    // Every validator over the cap is reported, along with the cap
+    let capped_layout = Layout {
+        max_commission_percentage: Some(9),
+        ..layout.clone()
+    };
+    client.put(Path::new(LAYOUT_FILE), &capped_layout).unwrap();
+    let err = fetch_mainnet_genesis_info(git_options.clone(), &GenesisOptions::default())
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(err.contains("(10%)"), "{}", err);
+    assert!(!err.contains("(0%)"), "{}", err);
+    assert!(err.contains("max_commission_percentage 9%"), "{}", err);
+
+    // A cap over 100% is a bad layout
+    assert!(Layout {
+        max_commission_percentage: Some(101),
+        ..layout
+    }
+    .check_max_commission_percentage()
+    .is_err());
+}
+
+#[tokio::test]
+async fn test_employee_vesting_schedule() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let git_options = setup_mainnet_git_dir(&dir, vec![]).await;
+    let client = git_options.clone().get_client().unwrap();
+    let employee_file = Path::new(EMPLOYEE_VESTING_ACCOUNTS_FILE);
+    let pools: EmployeePoolMap = client.get(employee_file).unwrap();
+    let pool = pools.inner[0].clone();
+    pool.check_vesting_schedule().unwrap();
+
+    let schedule = |numerators: Vec<u64>, denominator: u64| EmployeePoolConfig {
+        vesting_schedule_numerators: numerators,
+        vesting_schedule_denominator: denominator,
+        ..pool.clone()
+    };
+    // A cliff, exact sums, and a repeated last fraction are all fine
+    for (numerators, denominator) in [
+        (vec![0, 0, 12, 1], 48),
+        (vec![1, 1], 2),
+        (vec![1], 4),
+        (vec![1, 0], 1),
+    ] {
+        schedule(numerators.clone(), denominator)
+            .check_vesting_schedule()
+            .unwrap_or_else(|err| panic!("{:?}/{}: {}", numerators, denominator, err));
+    }
+    for (numerators, denominator, error) in [
+        (vec![], 48, "no numerators"),
+        (vec![1], 0, "denominator is 0"),
+        (vec![25, 24], 48, "add up over"),
+        (vec![0, 0, 0], 48, "cliff never ends"),
+        (vec![3, 0], 48, "cliff never ends"),
+        (vec![3, 3, 5], 48, "last one 5 being repeated"),
+        (vec![1, u64::MAX - 1], u64::MAX, "too small"),
+    ] {
+        let err = schedule(numerators.clone(), denominator)
+            .check_vesting_schedule()
+            .unwrap_err();
+        assert!(
+            err.contains(error),
+            "{:?}/{}: {}",
+            numerators,
+            denominator,
+            err
+        );
+    }
+
+    // Genesis reports which pool is malformed
+    let mut bad_pools = pools;
+    bad_pools.inner[1] = EmployeePoolConfig {
+        vesting_schedule_numerators: vec![0, 0, 0],
+        ..bad_pools.inner[1].clone()
+    };
+    client.put(employee_file, &bad_pools).unwrap();
+    let err = fetch_mainnet_genesis_info(git_options, &GenesisOptions::default())
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("cliff never ends"), "{}", err);
+    assert!(err.to_string().contains("pool #1"), "{}", err);
+}
+
+#[test]
+fn test_prompt_layout() {
+    // Invalid values are re-prompted, and empty values keep the default
+    let input = [
+        "testnet",         // chain id
+        "false",           // is test
+        "",                // allow new validators
+        "not a number",    // epoch duration
+        "3600",            // epoch duration
+        "",                // min stake
+        "1",               // max stake, under the min stake
+        "200000000000000", // max stake
+        "60",              // lockup, shorter than an epoch
+        "7200",            // lockup
+        "",                // min voting threshold
+        "",                // required proposer stake
+        "",                // voting duration, longer than the lockup
+        "3600",            // voting duration
+        "101",             // rewards apy
+        "",                // rewards apy
+        "0",               // voting power increase limit
+        "30",              // voting power increase limit
+    ]
+    .join("\n");
+    let mut output = Vec::new();
+    let layout = prompt_layout(&mut input.as_bytes(), &mut output).unwrap();
+    let defaults = Layout::default();
+    assert_eq!(ChainId::new(NamedChain::TESTNET.id()), layout.chain_id);
+    assert!(!layout.is_test);
+    assert_eq!(defaults.allow_new_validators, layout.allow_new_validators);
+    assert_eq!(3600, layout.epoch_duration_secs);
+    assert_eq!(defaults.min_stake, layout.min_stake);
+    assert_eq!(200_000_000_000_000, layout.max_stake);
+    assert_eq!(7200, layout.recurring_lockup_duration_secs);
+    assert_eq!(3600, layout.voting_duration_secs);
+    assert_eq!(
+        defaults.rewards_apy_percentage,
+        layout.rewards_apy_percentage
+    );
+    assert_eq!(30, layout.voting_power_increase_limit);
+
+    // Accepted values always pass the layout checks
+    layout.validate().unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("Invalid value 'not a number'"));
+    assert!(output.contains("must not be greater than max_stake"));
+    assert!(output.contains("must be at least epoch_duration_secs"));
+
+    // Running out of input fails rather than using defaults
+    assert!(prompt_layout(&mut "testnet\n".as_bytes(), &mut Vec::new()).is_err());
+}
+
+#[test]
+fn test_layout_duration_bounds() {
+    // The bounds are inclusive
+    let layout = Layout {
+        epoch_duration_secs: MIN_EPOCH_DURATION_SECS,
+        recurring_lockup_duration_secs: MAX_RECURRING_LOCKUP_DURATION_SECS,
+        voting_duration_secs: MIN_VOTING_DURATION_SECS,
+        ..Default::default()
+    };
+    assert!(layout.validate().is_ok());
+
+    // Durations outside of the bounds name the field and value
+    let long_durations = Layout {
+        epoch_duration_secs: MAX_EPOCH_DURATION_SECS + 1,
+        recurring_lockup_duration_secs: MAX_RECURRING_LOCKUP_DURATION_SECS + 1,
+        ..Default::default()
+    };
+    let errors = long_durations.validate().unwrap_err().join("; ");
+    assert!(
+        errors.contains(&format!(
+            "epoch_duration_secs {} must be at most {}",
+            MAX_EPOCH_DURATION_SECS + 1,
+            MAX_EPOCH_DURATION_SECS
+        )),
+        "{}",
+        errors
+    );
+    assert!(
+        errors.contains(&format!(
+            "recurring_lockup_duration_secs {} must be at most {}",
+            MAX_RECURRING_LOCKUP_DURATION_SECS + 1,
+            MAX_RECURRING_LOCKUP_DURATION_SECS
+        )),
+        "{}",
+        errors
+    );
+
+    let short_durations = Layout {
+        epoch_duration_secs: 1,
+        recurring_lockup_duration_secs: 1,
+        voting_duration_secs: 1,
+        ..Default::default()
+    };
+    let errors = short_durations.validate().unwrap_err().join("; ");
+    assert!(
+        errors.contains(&format!(
+            "epoch_duration_secs 1 must be at least {}",
+            MIN_EPOCH_DURATION_SECS
+        )),
+        "{}",
+        errors
+    );
+    assert!(
+        errors.contains(&format!(
+            "voting_duration_secs 1 must be at least {}",
+            MIN_VOTING_DURATION_SECS
+        )),
+        "{}",
+        errors
+    );
+}
+
+#[test]
+fn test_layout_named_chain_id() {
+    let layout_yaml = to_yaml(&Layout::default())
+        .unwrap()
+        .replace("chain_id: 4", "chain_id: premainnet");
+    let layout: Layout = from_yaml(&layout_yaml).unwrap();
+    assert_eq!(ChainId::new(NamedChain::PREMAINNET.id()), layout.chain_id);
+}
+
+#[test]
+fn test_resolve_chain_id() {
+    let test_layout = Layout {
+        chain_id: ChainId::mainnet(),
+        is_test: true,
+        ..Default::default()
+    };
+
+    // The mainnet chain id can only be used for mainnet, unless explicitly allowed
+    assert!(resolve_chain_id(&test_layout, &GenesisOptions::default(), false).is_err());
+    assert_eq!(
+        ChainId::mainnet(),
+        resolve_chain_id(&test_layout, &GenesisOptions::default(), true).unwrap()
+    );
+    let allow_reserved = GenesisOptions {
+        allow_reserved_chain_id: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        ChainId::mainnet(),
+        resolve_chain_id(&test_layout, &allow_reserved, false).unwrap()
+    );
+
+    // Symbolic overrides replace the layout's chain id
+    let testnet_override = GenesisOptions {
+        chain_id: Some(ChainId::from_str("testnet").unwrap()),
+        ..Default::default()
+    };
+    assert!(resolve_chain_id(&test_layout, &testnet_override, false).is_err());
+    let non_test_layout = Layout {
+        is_test: false,
+        ..Default::default()
+    };
+    assert_eq!(
+        ChainId::new(NamedChain::TESTNET.id()),
+        resolve_chain_id(&non_test_layout, &testnet_override, false).unwrap()
+    );
+    let custom_override = GenesisOptions {
+        chain_id: Some(ChainId::from_str("42").unwrap()),
+        ..Default::default()
+    };
+    assert_eq!(
+        ChainId::new(42),
+        resolve_chain_id(&test_layout, &custom_override, false).unwrap()
+    );
+}
+
+const GITHUB_OWNER: &str = "owner";
+const GITHUB_REPOSITORY: &str = "repository";
+const GITHUB_BRANCH: &str = "main";
+const GITHUB_FILE: &str = "layout.yaml";
+
+/// A Github client against a mock server, reading its token from the environment variable
+fn mock_github_client(server: &MockServer, token_env: &str) -> Client {
+    let token_source = GithubTokenSource::Env(token_env.to_string());
+    let client = GithubClient::new_with_url(
+        server.base_url(),
+        GITHUB_OWNER.to_string(),
+        GITHUB_REPOSITORY.to_string(),
+        GITHUB_BRANCH.to_string(),
+        token_source.read_token().unwrap(),
+    );
+    Client::new(Storage::Github(RateLimitedGithubClient::new(
+        client,
+        None,
+        0,
+        Some(token_source),
+    )))
+}
+
+fn mock_file_response(content: &str) -> serde_json::Value {
+    serde_json::json!({
+        "type": "file",
+        "path": GITHUB_FILE,
+        "sha": "sha",
+        "content": base64::encode(content),
+    })
+}
+
+#[test]
+fn test_github_token_from_env() {
+    let token_env = "APTOS_TEST_GITHUB_TOKEN_FROM_ENV";
+    std::env::set_var(token_env, " token \n");
+    let server = MockServer::start();
+    let file = server.mock(|when, then| {
+        when.method(GET)
+            .path(format!(
+                "/repos/{}/{}/contents/{}",
+                GITHUB_OWNER, GITHUB_REPOSITORY, GITHUB_FILE
+            ))
+            .query_param("ref", GITHUB_BRANCH)
+            .header("Authorization", "token token");
+        then.status(200).json_body(mock_file_response("value"));
+    });
+
+    let value: String = mock_github_client(&server, token_env)
+        .get(Path::new(GITHUB_FILE))
+        .unwrap();
+    assert_eq!("value", value);
+    file.assert();
+}
+
+#[test]
+fn test_github_token_refreshed_on_unauthorized() {
+    let token_env = "APTOS_TEST_GITHUB_TOKEN_REFRESHED";
+    std::env::set_var(token_env, "expired");
+    let server = MockServer::start();
+    let client = mock_github_client(&server, token_env);
+    let path = format!(
+        "/repos/{}/{}/contents/{}",
+        GITHUB_OWNER, GITHUB_REPOSITORY, GITHUB_FILE
+    );
+    let rejected = server.mock(|when, then| {
+        when.method(GET)
+            .path(path.clone())
+            .header("Authorization", "token expired");
+        then.status(401)
+            .json_body(serde_json::json!({ "message": "Bad credentials" }));
+    });
+    let accepted = server.mock(|when, then| {
+        when.method(GET)
+            .path(path.clone())
+            .header("Authorization", "token rotated");
+        then.status(200).json_body(mock_file_response("value"));
+    });
+
+    // The token is rotated after the client read it
+    std::env::set_var(token_env, "rotated");
+    let value: String = client.get(Path::new(GITHUB_FILE)).unwrap();
+    assert_eq!("value", value);
+    rejected.assert_hits(1);
+    accepted.assert_hits(1);
+
+    // A token that's still rejected after re-reading it is reported as bad credentials
+    std::env::set_var(token_env, "expired");
+    let client = mock_github_client(&server, token_env);
+    let err = client.get::<String>(Path::new(GITHUB_FILE)).unwrap_err();
+    assert!(err.to_string().contains("bad credentials"), "{}", err);
+    assert!(err.to_string().contains(token_env), "{}", err);
+    assert!(!err.to_string().contains("expired"), "{}", err);
+}
+
+#[test]
+fn test_github_repository_not_visible() {
+    let token_env = "APTOS_TEST_GITHUB_REPOSITORY_NOT_VISIBLE";
+    std::env::set_var(token_env, "token");
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path(format!(
+            "/repos/{}/{}/contents/{}",
+            GITHUB_OWNER, GITHUB_REPOSITORY, GITHUB_FILE
+        ));
+        then.status(404);
+    });
+    let repository = server.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/repos/{}/{}", GITHUB_OWNER, GITHUB_REPOSITORY));
+        then.status(404);
+    });
+
+    let err = mock_github_client(&server, token_env)
+        .get::<String>(Path::new(GITHUB_FILE))
+        .unwrap_err();
+    assert!(err.to_string().contains("not visible"), "{}", err);
+    repository.assert();
+}
+
+#[test]
+fn test_github_head_commit() {
+    let token_env = "APTOS_TEST_GITHUB_HEAD_COMMIT";
+    std::env::set_var(token_env, "token");
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path(format!(
+            "/repos/{}/{}/git/ref/heads/{}",
+            GITHUB_OWNER, GITHUB_REPOSITORY, GITHUB_BRANCH
+        ));
+        then.status(200)
+            .json_body(serde_json::json!({ "object": { "sha": "head" } }));
+    });
+    server.mock(|when, then| {
+        when.method(GET).path(format!(
+            "/repos/{}/{}/contents/{}",
+            GITHUB_OWNER, GITHUB_REPOSITORY, GITHUB_FILE
+        ));
+        then.status(200).json_body(mock_file_response("value"));
+    });
+
+    let client = mock_github_client(&server, token_env);
+    assert_eq!(Some("head".to_string()), client.head_commit().unwrap());
+    let _: String = client.get(Path::new(GITHUB_FILE)).unwrap();
+    assert_eq!(
+        Some(&hex::encode(Sha256::digest(b"value"))),
+        client.reads().get(Path::new(GITHUB_FILE))
+    );
+}
+
+#[test]
+fn test_github_commit_is_single_commit() {
+    let token_env = "APTOS_TEST_GITHUB_COMMIT";
+    std::env::set_var(token_env, "token");
+    let server = MockServer::start();
+    let git_path =
+        |path: &str| format!("/repos/{}/{}/git/{}", GITHUB_OWNER, GITHUB_REPOSITORY, path);
+    server.mock(|when, then| {
+        when.method(GET)
+            .path(git_path(&format!("ref/heads/{}", GITHUB_BRANCH)));
+        then.status(200)
+            .json_body(serde_json::json!({ "object": { "sha": "parent" } }));
+    });
+    server.mock(|when, then| {
+        when.method(GET).path(git_path("commits/parent"));
+        then.status(200)
+            .json_body(serde_json::json!({ "tree": { "sha": "base_tree" } }));
+    });
+    let blobs = server.mock(|when, then| {
+        when.method(POST).path(git_path("blobs"));
+        then.status(201)
+            .json_body(serde_json::json!({ "sha": "blob" }));
+    });
+    let tree = server.mock(|when, then| {
+        when.method(POST)
+            .path(git_path("trees"))
+            .json_body_partial(r#"{ "base_tree": "base_tree" }"#);
+        then.status(201)
+            .json_body(serde_json::json!({ "sha": "tree" }));
+    });
+    let commit = server.mock(|when, then| {
+        when.method(POST)
+            .path(git_path("commits"))
+            .json_body_partial(r#"{ "tree": "tree", "parents": ["parent"] }"#);
+        then.status(201)
+            .json_body(serde_json::json!({ "sha": "commit" }));
+    });
+    let update_ref = server.mock(|when, then| {
+        when.method(PATCH)
+            .path(git_path(&format!("refs/heads/{}", GITHUB_BRANCH)))
+            .json_body(serde_json::json!({ "sha": "commit" }));
+        then.status(200);
+    });
+
+    let mut changes = GitChanges::default();
+    changes.put(Path::new(OWNER_FILE), "owner").unwrap();
+    changes.put(Path::new(OPERATOR_FILE), "operator").unwrap();
+    mock_github_client(&server, token_env)
+        .commit(changes, "message")
+        .unwrap();
+    blobs.assert_hits(2);
+    tree.assert();
+    commit.assert();
+    update_ref.assert();
+}
+
+#[test]
+fn test_github_enterprise_api_url() {
+    let token_env = "APTOS_TEST_GITHUB_ENTERPRISE";
+    std::env::set_var(token_env, "token");
+    let server = MockServer::start();
+    let contents_path = format!(
+        "/api/v3/repos/{}/{}/contents/{}",
+        GITHUB_OWNER, GITHUB_REPOSITORY, GITHUB_FILE
+    );
+    let read = server.mock(|when, then| {
+        when.method(GET).path(contents_path.clone());
+        then.status(200).json_body(mock_file_response("value"));
+    });
+    let write = server.mock(|when, then| {
+        when.method(PUT)
+            .path(contents_path.clone())
+            .json_body_partial(r#"{ "sha": "sha" }"#);
+        then.status(200);
+    });
+    let git_options = |api_url: String| GitOptions {
+        github_repository: Some(
+            GithubRepo::from_str(&format!("{}/{}", GITHUB_OWNER, GITHUB_REPOSITORY)).unwrap(),
+        ),
+        github_branch: GITHUB_BRANCH.to_string(),
+        github_token_env: Some(token_env.to_string()),
+        github_api_url: Some(api_url),
+        ..Default::default()
+    };
+
+    // Reads and writes go under the Enterprise Server API prefix
+    let client = git_options(server.url("/api/v3")).get_client().unwrap();
+    let value: String = client.get(Path::new(GITHUB_FILE)).unwrap();
+    assert_eq!("value", value);
+    client.put(Path::new(GITHUB_FILE), "new value").unwrap();
+    write.assert();
+
+    // A bare host gets the prefix added
+    let client = git_options(server.base_url()).get_client().unwrap();
+    let value: String = client.get(Path::new(GITHUB_FILE)).unwrap();
+    assert_eq!("value", value);
+    read.assert_hits(3);
+
+    // Errors say which API was used
+    let wrong_url = server.url("/wrong");
+    let err = git_options(wrong_url.clone())
+        .get_client()
+        .unwrap()
+        .get::<String>(Path::new(GITHUB_FILE))
+        .unwrap_err();
+    assert!(err.to_string().contains(&wrong_url), "{}", err);
+
+    // A CA certificate that can't be parsed is rejected up front
+    let ca_cert_file = TempPath::new();
+    write_to_file(ca_cert_file.path(), "ca cert", b"not a certificate").unwrap();
+    let err = GitOptions {
+        ca_cert_file: Some(PathBuf::from(ca_cert_file.path())),
+        ..git_options(server.url("/api/v3"))
+    }
+    .get_client()
+    .err()
+    .unwrap();
+    assert!(
+        err.to_string().contains("Failed to use CA certificate"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_local_commit_is_all_or_nothing() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let client = Client::local(PathBuf::from(dir.path()));
+    client.put(Path::new(LAYOUT_FILE), "layout").unwrap();
+
+    // The last file can't be written, since its directory is a file
+    let mut changes = GitChanges::default();
+    changes.put(Path::new(LAYOUT_FILE), "new layout").unwrap();
+    changes.put(Path::new(OWNER_FILE), "owner").unwrap();
+    changes
+        .put(&Path::new(OWNER_FILE).join(OPERATOR_FILE), "operator")
+        .unwrap();
+    let err = client.commit(changes, "message").unwrap_err();
+    assert!(err.to_string().contains("no files were changed"), "{}", err);
+    assert_eq!(
+        "layout",
+        client.get::<String>(Path::new(LAYOUT_FILE)).unwrap()
+    );
+    assert!(!dir.path().join(OWNER_FILE).exists());
+
+    // Otherwise all of the files are written
+    let mut changes = GitChanges::default();
+    changes.put(Path::new(LAYOUT_FILE), "new layout").unwrap();
+    changes.put(Path::new(OWNER_FILE), "owner").unwrap();
+    client.commit(changes, "message").unwrap();
+    assert_eq!(
+        "new layout",
+        client.get::<String>(Path::new(LAYOUT_FILE)).unwrap()
+    );
+    assert_eq!(
+        "owner",
+        client.get::<String>(Path::new(OWNER_FILE)).unwrap()
+    );
+}
+
+/// Setup a mainnet repo with the `unreferenced_accounts` getting balances but not being used by
+/// anything
+///
+/// Tests depend on exactly this, so changes here need every caller to be checked:
+/// - `user-0` and `user-1` are standalone validators joining during genesis, with commissions of
+///   10% and 0%.  Each user's owner, operator, and voter are one account, and every host is on
+///   `localhost`
+/// - Two employee pools of two accounts each, `0x201`/`0x202` joining during genesis and
+///   `0x203`/`0x204` not, administered by a third generated identity
+/// - Every account gets `INITIAL_BALANCE`, and the layout's total supply is their sum
+async fn setup_mainnet_git_dir(
+    dir: &TempPath,
+    unreferenced_accounts: Vec<AccountAddress>,
+) -> GitOptions {
+    let git_options = create_users(2, dir, &mut vec![10, 0]).await;
+
+    let employee_1 = AccountAddress::from_hex_literal("0x201").unwrap();
+    let employee_2 = AccountAddress::from_hex_literal("0x202").unwrap();
+    let employee_3 = AccountAddress::from_hex_literal("0x203").unwrap();
+    let employee_4 = AccountAddress::from_hex_literal("0x204").unwrap();
+    let admin_dir = generate_keys(dir.path(), 2).await;
+    let admin_identity: PublicIdentity = from_yaml(
+        &String::from_utf8(read_from_file(admin_dir.join(PUBLIC_KEYS_FILE).as_path()).unwrap())
+            .unwrap(),
+    )
+    .unwrap();
+
+    // Create initial balances and employee vesting account files.
+    let git_dir = git_options.local_repository_dir.as_ref().unwrap().as_path();
+
+    // Load the standalone staking pools
+    let staker_0 = get_owner_address(git_options.clone(), 0);
+    let staker_1 = get_owner_address(git_options.clone(), 1);
+
+    let mut accounts = vec![
+        staker_0,
+        staker_1,
+        employee_1,
+        employee_2,
+        employee_3,
+        employee_4,
+        admin_identity.account_address,
+    ];
+    accounts.extend(unreferenced_accounts);
+
+    // Ensure the total supply matches the balances, which has to fit in a u64 like the balances
+    let total_supply = accounts
+        .iter()
+        .try_fold(0u64, |total, _| total.checked_add(INITIAL_BALANCE))
+        .expect("Balances of the mainnet test repo overflow the total supply");
+    let git_client = git_options.clone().get_client().unwrap();
+    let mut layout: Layout = git_client.get(Path::new(LAYOUT_FILE)).unwrap();
+    layout.total_supply = Some(total_supply);
+    git_client.put(Path::new(LAYOUT_FILE), &layout).unwrap();
+
+    create_account_balances_file(PathBuf::from(git_dir), accounts).await;
+    create_employee_vesting_accounts_file(
+        PathBuf::from(git_dir),
+        admin_identity,
+        &vec![vec![employee_1, employee_2], vec![employee_3, employee_4]],
+        &[true, false],
+    )
+    .await;
+    git_options
+}
+
+fn read_yaml_file<T: DeserializeOwned>(path: &Path) -> T {
+    from_yaml(&String::from_utf8(read_from_file(path).unwrap()).unwrap()).unwrap()
+}
+
 fn get_owner_address(git_options: GitOptions, index: u64) -> AccountAddress {
     let git_client = git_options.get_client().unwrap();
     let owner_config: OwnerConfiguration = git_client
@@ -176,6 +3214,15 @@ async fn generate_genesis(git_options: GitOptions, output_dir: PathBuf, mainnet:
         git_options,
         output_dir: Some(output_dir),
         mainnet,
+        print_validator_set_hash: false,
+        genesis_size_budget_mb: DEFAULT_GENESIS_SIZE_BUDGET_MB,
+        max_genesis_size_mb: None,
+        emit_json_genesis: false,
+        waypoint_encoding: WaypointEncoding::Plain,
+        manifest: None,
+        verify_output: false,
+        deep_verify: false,
+        genesis_options: GenesisOptions::default(),
     };
     let _ = command.execute().await.unwrap();
 }
@@ -233,6 +3280,7 @@ async fn create_layout_file(
 ) {
     GenerateLayoutTemplate {
         output_file: PathBuf::from(file),
+        interactive: false,
         prompt_options: PromptOptions::yes(),
     }
     .execute()
@@ -264,6 +3312,8 @@ async fn generate_keys(dir: &Path, index: u8) -> PathBuf {
         rng_args: RngArgs::from_seed([index; 32]),
         prompt_options: PromptOptions::yes(),
         output_dir: Some(output_dir.clone()),
+        split_role_files: false,
+        operator_address: None,
     };
     let _ = command.execute().await.unwrap();
 
@@ -288,6 +3338,7 @@ async fn set_validator_config(
         voter_public_identity_file: None,
         commission_percentage,
         join_during_genesis: true,
+        private_validator_address: false,
     };
 
     command.execute().await.unwrap()
@@ -328,11 +3379,11 @@ async fn create_employee_vesting_accounts_file(
 
                 ValidatorConfiguration {
                     owner_account_address: admin_identity.account_address,
-                    owner_account_public_key: admin_identity.account_public_key.clone(),
+                    owner_account_public_key: admin_identity.account_public_key.clone().into(),
                     operator_account_address: admin_identity.account_address,
-                    operator_account_public_key: admin_identity.account_public_key.clone(),
+                    operator_account_public_key: admin_identity.account_public_key.clone().into(),
                     voter_account_address: admin_identity.account_address,
-                    voter_account_public_key: admin_identity.account_public_key,
+                    voter_account_public_key: admin_identity.account_public_key.into(),
                     consensus_public_key: admin_identity.consensus_public_key,
                     proof_of_possession: admin_identity.consensus_proof_of_possession,
                     validator_network_public_key: admin_identity.validator_network_public_key,
@@ -342,15 +3393,16 @@ async fn create_employee_vesting_accounts_file(
                     stake_amount: 2 * INITIAL_BALANCE,
                     commission_percentage: 0,
                     join_during_genesis: true,
+                    publish_validator_address: true,
                 }
             } else {
                 ValidatorConfiguration {
                     owner_account_address: admin_identity.account_address,
-                    owner_account_public_key: admin_identity.account_public_key.clone(),
+                    owner_account_public_key: admin_identity.account_public_key.clone().into(),
                     operator_account_address: admin_identity.account_address,
-                    operator_account_public_key: admin_identity.account_public_key.clone(),
+                    operator_account_public_key: admin_identity.account_public_key.clone().into(),
                     voter_account_address: admin_identity.account_address,
-                    voter_account_public_key: admin_identity.account_public_key.clone(),
+                    voter_account_public_key: admin_identity.account_public_key.clone().into(),
                     consensus_public_key: None,
                     proof_of_possession: None,
                     validator_network_public_key: None,
@@ -360,6 +3412,7 @@ async fn create_employee_vesting_accounts_file(
                     stake_amount: 2 * INITIAL_BALANCE,
                     commission_percentage: 0,
                     join_during_genesis: false,
+                    publish_validator_address: true,
                 }
             };
 
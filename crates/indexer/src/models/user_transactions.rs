@@ -14,6 +14,7 @@ use aptos_api_types::{TransactionPayload, UserTransaction as APIUserTransaction}
 use bigdecimal::BigDecimal;
 use field_count::FieldCount;
 use serde::{Deserialize, Serialize};
+use sha2::Digest;
 
 #[derive(
     Associations,
@@ -66,12 +67,7 @@ impl UserTransaction {
                 gas_unit_price: u64_to_bigdecimal(txn.request.gas_unit_price.0),
                 timestamp: parse_timestamp(txn.timestamp.0, version),
                 inserted_at: chrono::Utc::now().naive_utc(),
-                entry_function_id_str: match &txn.request.payload {
-                    TransactionPayload::EntryFunctionPayload(payload) => {
-                        payload.function.to_string()
-                    }
-                    _ => String::default(),
-                },
+                entry_function_id_str: Self::get_entry_function_id_str(&txn.request.payload),
             },
             txn.request
                 .signature
@@ -88,7 +84,66 @@ impl UserTransaction {
                 .unwrap_or_default(), // empty vec if signature is None
         )
     }
+
+    /// Scripts have no name, so they're identified by the SHA-256 hash of their bytecode as
+    /// `script::<hash>`.  Module bundles have no identifier.
+    pub fn get_entry_function_id_str(payload: &TransactionPayload) -> String {
+        match payload {
+            TransactionPayload::EntryFunctionPayload(payload) => payload.function.to_string(),
+            TransactionPayload::ScriptPayload(payload) => format!(
+                "script::{}",
+                hex::encode(sha2::Sha256::digest(&payload.code.bytecode.0))
+            ),
+            _ => String::default(),
+        }
+    }
 }
 
 // Prevent conflicts with other things named `Transaction`
 pub type UserTransactionModel = UserTransaction;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_api_types::{
+        EntryFunctionPayload, HexEncodedBytes, MoveScriptBytecode, ScriptPayload,
+    };
+
+    #[test]
+    fn test_get_entry_function_id_str() {
+        let entry_function = TransactionPayload::EntryFunctionPayload(EntryFunctionPayload {
+            function: "0x1::coin::transfer".parse().unwrap(),
+            type_arguments: vec![],
+            arguments: vec![],
+        });
+        assert_eq!(
+            "0x1::coin::transfer",
+            UserTransaction::get_entry_function_id_str(&entry_function)
+        );
+
+        let script = |bytecode: Vec<u8>| {
+            TransactionPayload::ScriptPayload(ScriptPayload {
+                code: MoveScriptBytecode {
+                    bytecode: HexEncodedBytes(bytecode),
+                    abi: None,
+                },
+                type_arguments: vec![],
+                arguments: vec![],
+            })
+        };
+        let script_id = UserTransaction::get_entry_function_id_str(&script(vec![1, 2, 3]));
+        assert_eq!(
+            "script::039058c6f2c0cb492c533b0a4d14ef77cc0f78abccced5287d84a1a2011cfb81",
+            script_id
+        );
+        // The same script always gets the same identifier, and different scripts don't share one
+        assert_eq!(
+            script_id,
+            UserTransaction::get_entry_function_id_str(&script(vec![1, 2, 3]))
+        );
+        assert_ne!(
+            script_id,
+            UserTransaction::get_entry_function_id_str(&script(vec![1, 2, 4]))
+        );
+    }
+}
@@ -6,13 +6,15 @@
 use proxy::Proxy;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::sync::Arc;
 use thiserror::Error;
 
 /// Request timeout for github operations
 const ACCEPT_HEADER: &str = "Accept";
 const ACCEPT_VALUE: &str = "Accept: application/vnd.github.v3+json";
 const TIMEOUT: u64 = 10_000;
-const URL: &str = "https://api.github.com";
+/// API URL of github.com, Enterprise Server instances serve the API under their own host
+pub const URL: &str = "https://api.github.com";
 
 #[derive(Debug, Error, PartialEq, Eq)]
 pub enum Error {
@@ -68,18 +70,60 @@ pub struct Client {
     owner: String,
     repository: String,
     token: String,
+    url: String,
+    tls_connector: Option<Arc<native_tls::TlsConnector>>,
 }
 
 impl Client {
     pub fn new(owner: String, repository: String, branch: String, token: String) -> Self {
+        Self::new_with_url(URL.to_string(), owner, repository, branch, token)
+    }
+
+    /// Creates a client against a different API URL than GitHub's public one
+    pub fn new_with_url(
+        url: String,
+        owner: String,
+        repository: String,
+        branch: String,
+        token: String,
+    ) -> Self {
         Self {
             branch,
             owner,
             repository,
             token,
+            url: url.trim_end_matches('/').to_string(),
+            tls_connector: None,
         }
     }
 
+    /// API URL that requests are made against
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Trust an additional root certificate, for servers with a private certificate authority
+    ///
+    /// The certificate can be PEM or DER encoded.
+    pub fn set_ca_certificate(&mut self, certificate: &[u8]) -> Result<(), Error> {
+        let certificate = native_tls::Certificate::from_pem(certificate)
+            .or_else(|_| native_tls::Certificate::from_der(certificate))
+            .map_err(|err| Error::InternalError(format!("Invalid CA certificate: {}", err)))?;
+        let mut tls_builder = native_tls::TlsConnector::builder();
+        tls_builder.min_protocol_version(Some(native_tls::Protocol::Tlsv12));
+        tls_builder.add_root_certificate(certificate);
+        let tls_connector = tls_builder
+            .build()
+            .map_err(|err| Error::InternalError(err.to_string()))?;
+        self.tls_connector = Some(Arc::new(tls_connector));
+        Ok(())
+    }
+
+    /// Replace the token used for requests e.g. after it has been rotated
+    pub fn set_token(&mut self, token: String) {
+        self.token = token;
+    }
+
     /// Delete a file from a GitHub repository
     pub fn delete_file(&self, path: &str) -> Result<(), Error> {
         // Occasionally GitHub sends us back delayed results and the file is already deleted.
@@ -114,9 +158,30 @@ impl Client {
         Ok(())
     }
 
+    /// Checks that the repository is visible with the token
+    ///
+    /// GitHub returns a 404 rather than a 401 for private repositories that the token can't
+    /// access, so this can be used to tell a missing file apart from an invisible repository.
+    pub fn get_repository(&self) -> Result<(), Error> {
+        let url = format!("{}/repos/{}/{}", self.url, self.owner, self.repository);
+        let resp = self.upgrade_request(ureq::get(&url)).call();
+
+        match resp.status() {
+            200 => Ok(()),
+            404 => Err(Error::NotFound(format!(
+                "{}/{}",
+                self.owner, self.repository
+            ))),
+            _ => Err(resp.into()),
+        }
+    }
+
     /// Retrieve a list of branches, this is effectively a status check on the repository
     pub fn get_branches(&self) -> Result<Vec<String>, Error> {
-        let url = format!("{}/repos/{}/{}/branches", URL, self.owner, self.repository);
+        let url = format!(
+            "{}/repos/{}/{}/branches",
+            self.url, self.owner, self.repository
+        );
         let resp = self.upgrade_request(ureq::get(&url)).call();
 
         match resp.status() {
@@ -187,12 +252,85 @@ impl Client {
         }
     }
 
+    /// Retrieves the sha hash of the commit at the head of the branch
+    pub fn get_branch_commit(&self) -> Result<String, Error> {
+        let resp = self
+            .upgrade_request(ureq::get(
+                &self.git_url(&format!("ref/heads/{}", self.branch)),
+            ))
+            .call();
+        match resp.status() {
+            200 => Ok(serde_json::from_str::<RefResponse>(&resp.into_string()?)?
+                .object
+                .sha),
+            404 => Err(Error::NotFound(self.branch.clone())),
+            _ => Err(resp.into()),
+        }
+    }
+
+    /// Create or update several files in a single commit on the branch
+    ///
+    /// This uses the git data API, so either every file is updated or none are.  Contents are
+    /// the raw file bytes.
+    pub fn put_files(&self, files: &[(String, Vec<u8>)], message: &str) -> Result<(), Error> {
+        let ref_url = self.git_url(&format!("refs/heads/{}", self.branch));
+        let parent = self.get_branch_commit()?;
+
+        let resp = self
+            .upgrade_request(ureq::get(&self.git_url(&format!("commits/{}", parent))))
+            .call();
+        let base_tree = match resp.status() {
+            200 => {
+                serde_json::from_str::<CommitResponse>(&resp.into_string()?)?
+                    .tree
+                    .sha
+            }
+            _ => return Err(resp.into()),
+        };
+
+        let mut tree = Vec::new();
+        for (path, content) in files {
+            let blob = self.create(
+                "blobs",
+                json!({ "content": base64::encode(content), "encoding": "base64" }),
+            )?;
+            tree.push(json!({ "path": path, "mode": "100644", "type": "blob", "sha": blob }));
+        }
+        let tree = self.create("trees", json!({ "base_tree": base_tree, "tree": tree }))?;
+        let commit = self.create(
+            "commits",
+            json!({ "message": format!("[aptos-management] {}", message), "tree": tree, "parents": [parent] }),
+        )?;
+
+        let resp = self
+            .upgrade_request(ureq::request("PATCH", &ref_url))
+            .send_json(json!({ "sha": commit }));
+        match resp.status() {
+            200 => Ok(()),
+            _ => Err(resp.into()),
+        }
+    }
+
+    /// Creates a git object, and returns its sha hash
+    fn create(&self, object_type: &str, json: serde_json::Value) -> Result<String, Error> {
+        let resp = self
+            .upgrade_request(ureq::post(&self.git_url(object_type)))
+            .send_json(json);
+        match resp.status() {
+            201 => Ok(serde_json::from_str::<ShaResponse>(&resp.into_string()?)?.sha),
+            _ => Err(resp.into()),
+        }
+    }
+
     /// Simple wrapper around requests to add default parameters to the request
     fn upgrade_request(&self, mut request: ureq::Request) -> ureq::Request {
         request
             .set("Authorization", &format!("token {}", self.token))
             .set(ACCEPT_HEADER, ACCEPT_VALUE)
             .timeout_connect(TIMEOUT);
+        if let Some(ref tls_connector) = self.tls_connector {
+            request.set_tls_connector(tls_connector.clone());
+        }
 
         let proxy = Proxy::new();
         let host = request.get_host().expect("unable to get the host");
@@ -246,14 +384,21 @@ impl Client {
     fn post_url(&self, path: &str) -> String {
         format!(
             "{}/repos/{}/{}/contents/{}",
-            URL, self.owner, self.repository, path
+            self.url, self.owner, self.repository, path
+        )
+    }
+
+    fn git_url(&self, path: &str) -> String {
+        format!(
+            "{}/repos/{}/{}/git/{}",
+            self.url, self.owner, self.repository, path
         )
     }
 
     fn get_url(&self, path: &str) -> String {
         format!(
             "{}/repos/{}/{}/contents/{}?ref={}",
-            URL, self.owner, self.repository, path, self.branch
+            self.url, self.owner, self.repository, path, self.branch
         )
     }
 }
@@ -263,6 +408,21 @@ struct Branch {
     name: String,
 }
 
+#[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
+struct ShaResponse {
+    sha: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
+struct RefResponse {
+    object: ShaResponse,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
+struct CommitResponse {
+    tree: ShaResponse,
+}
+
 #[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
 struct GetResponse {
     #[serde(rename = "type")]